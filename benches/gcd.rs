@@ -0,0 +1,58 @@
+//! Benchmarks for checking that Lehmer's GCD beats binary GCD even on small multi-word numbers, which is why
+//! `UBigNumber::gcd` only uses binary GCD once it's down to single words.
+//! 
+//! Run with `cargo bench --bench gcd`. The binary GCD here is the plain multi-word version of Stein's algorithm.
+
+#![feature(test)]
+
+extern crate test;
+
+use std::mem::swap;
+
+use sylvan_number::ubignumber::UBigNumber;
+use test::Bencher;
+
+fn operands(len: usize) -> (UBigNumber, UBigNumber) {
+	(UBigNumber::rand(len), UBigNumber::rand(len))
+}
+
+macro_rules! bench_algorithm {
+	($name:ident, $algorithm:expr, $len:expr) => {
+		#[bench]
+		fn $name(b: &mut Bencher) {
+			let (lhs, rhs) = operands($len);
+			b.iter(|| $algorithm(&lhs, &rhs));
+		}
+	};
+}
+
+fn lehmer(lhs: &UBigNumber, rhs: &UBigNumber) -> UBigNumber {
+	lhs.gcd(rhs)
+}
+
+fn binary(lhs: &UBigNumber, rhs: &UBigNumber) -> UBigNumber {
+	let (mut a, mut b) = (lhs.clone(), rhs.clone());
+	let (Some(a_zeros), Some(b_zeros)) = (a.trailing_zeros(), b.trailing_zeros()) else {
+		return a + b;
+	};
+
+	a >>= a_zeros;
+	while let Some(b_zeros) = b.trailing_zeros() {
+		b >>= b_zeros;
+		if a > b {
+			swap(&mut a, &mut b);
+		}
+		b -= &a;
+	}
+
+	a << a_zeros.min(b_zeros)
+}
+
+// MARK: Lehmer vs. Binary
+
+bench_algorithm!(lehmer_2, lehmer, 2);
+bench_algorithm!(lehmer_4, lehmer, 4);
+bench_algorithm!(lehmer_8, lehmer, 8);
+bench_algorithm!(binary_2, binary, 2);
+bench_algorithm!(binary_4, binary, 4);
+bench_algorithm!(binary_8, binary, 8);
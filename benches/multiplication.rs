@@ -0,0 +1,71 @@
+//! Benchmarks for picking the crossover points between the multiplication algorithms in `int_utility`.
+//! 
+//! Run with `cargo bench --bench multiplication`. Each algorithm is benchmarked one level deep at sizes around the
+//! thresholds, so the recursive calls all go through the same dispatcher and only the top level differs.
+
+#![feature(test)]
+
+extern crate test;
+
+use sylvan_number::{int_utility, ubignumber::UBigNumber};
+use test::Bencher;
+
+fn operands(len: usize) -> (Vec<u64>, Vec<u64>) {
+	(UBigNumber::rand(len).words, UBigNumber::rand(len).words)
+}
+
+macro_rules! bench_algorithm {
+	($name:ident, $algorithm:expr, $len:expr) => {
+		#[bench]
+		fn $name(b: &mut Bencher) {
+			let (lhs, rhs) = operands($len);
+			b.iter(|| $algorithm(&lhs, &rhs));
+		}
+	};
+}
+
+fn schoolbook(lhs: &[u64], rhs: &[u64]) -> Vec<u64> {
+	int_utility::word_mul(lhs.to_vec(), rhs.to_vec())
+}
+
+// MARK: Schoolbook vs. Karatsuba
+
+bench_algorithm!(schoolbook_064, schoolbook, 64);
+bench_algorithm!(schoolbook_080, schoolbook, 80);
+bench_algorithm!(schoolbook_096, schoolbook, 96);
+bench_algorithm!(schoolbook_128, schoolbook, 128);
+bench_algorithm!(karatsuba_064, int_utility::karatsuba_mul, 64);
+bench_algorithm!(karatsuba_080, int_utility::karatsuba_mul, 80);
+bench_algorithm!(karatsuba_096, int_utility::karatsuba_mul, 96);
+bench_algorithm!(karatsuba_128, int_utility::karatsuba_mul, 128);
+
+// MARK: Karatsuba vs. Toom-3
+
+bench_algorithm!(karatsuba_0400, int_utility::karatsuba_mul, 400);
+bench_algorithm!(karatsuba_0600, int_utility::karatsuba_mul, 600);
+bench_algorithm!(karatsuba_0800, int_utility::karatsuba_mul, 800);
+bench_algorithm!(karatsuba_1200, int_utility::karatsuba_mul, 1200);
+bench_algorithm!(toom_3_0400, int_utility::toom_3_mul, 400);
+bench_algorithm!(toom_3_0600, int_utility::toom_3_mul, 600);
+bench_algorithm!(toom_3_0800, int_utility::toom_3_mul, 800);
+bench_algorithm!(toom_3_1200, int_utility::toom_3_mul, 1200);
+
+// MARK: Toom-3 vs. Toom-4
+
+bench_algorithm!(toom_3_1500, int_utility::toom_3_mul, 1500);
+bench_algorithm!(toom_3_2000, int_utility::toom_3_mul, 2000);
+bench_algorithm!(toom_3_3000, int_utility::toom_3_mul, 3000);
+bench_algorithm!(toom_3_5000, int_utility::toom_3_mul, 5000);
+bench_algorithm!(toom_4_1500, int_utility::toom_4_mul, 1500);
+bench_algorithm!(toom_4_2000, int_utility::toom_4_mul, 2000);
+bench_algorithm!(toom_4_3000, int_utility::toom_4_mul, 3000);
+bench_algorithm!(toom_4_5000, int_utility::toom_4_mul, 5000);
+
+// MARK: Toom-4 vs. NTT
+
+bench_algorithm!(toom_4_16000, int_utility::toom_4_mul, 16000);
+bench_algorithm!(toom_4_32000, int_utility::toom_4_mul, 32000);
+bench_algorithm!(toom_4_48000, int_utility::toom_4_mul, 48000);
+bench_algorithm!(ntt_16000, int_utility::ntt_mul, 16000);
+bench_algorithm!(ntt_32000, int_utility::ntt_mul, 32000);
+bench_algorithm!(ntt_48000, int_utility::ntt_mul, 48000);
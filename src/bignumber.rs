@@ -1,10 +1,10 @@
 //! A signed, arbitrary sized interger
 
-use std::{cmp::Ordering, fmt::Debug, ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign}};
+use std::{cmp::Ordering, fmt::Debug, ops::{AddAssign, BitAndAssign, BitOrAssign, BitXorAssign, DivAssign, MulAssign, Neg, Not, RemAssign, SubAssign}};
 
 use algebra_kit::algebra::{EuclideanDomain, Ring};
 
-use crate::ubignumber::UBigNumber;
+use crate::{error::OutOfRangeError, primitive::Primitive, ubignumber::{UBigNumber, Word}};
 
 #[derive(Clone)]
 pub struct BigNumber {
@@ -16,7 +16,8 @@ impl BigNumber {
 
 	/// Creates a big number with a certain sign and magnitude
 	pub fn from_sign_magnitude(is_negative: bool, magnitude: UBigNumber) -> BigNumber {
-		BigNumber { is_negative, magnitude }
+		// zero is never negative, so that there is only one zero
+		BigNumber { is_negative: is_negative && !magnitude.is_zero(), magnitude }
 	}
 	
 	/// Creates a big number from an unsigned big number
@@ -24,11 +25,145 @@ impl BigNumber {
 		BigNumber::from_sign_magnitude(false, ubn)
 	}
 
+	/// Adds a number with the given sign and magnitude to this one
+	fn add_signed(&mut self, rhs_is_negative: bool, rhs_magnitude: &UBigNumber) {
+		if self.is_negative == rhs_is_negative {
+			self.magnitude += rhs_magnitude;
+		} else if self.magnitude >= *rhs_magnitude {
+			self.magnitude -= rhs_magnitude;
+		} else {
+			self.magnitude = rhs_magnitude - &self.magnitude;
+			self.is_negative = rhs_is_negative;
+		}
+
+		self.normalize_sign();
+	}
+
+	// MARK: Primitive Arithmetic
+
+	/// Promotes any primitive integer to a `BigNumber`
+	pub(crate) fn from_primitive<P: Primitive>(int: P) -> BigNumber {
+		let (is_negative, magnitude) = int.sign_magnitude();
+		BigNumber::from_sign_magnitude(is_negative, UBigNumber::from_primitive(magnitude))
+	}
+
+	// These all take the sign and magnitude of a primitive, and lean on the single word fast paths of the operators
+	// between `UBigNumber` and the unsigned primitives.
+
+	fn add_primitive(&mut self, (is_negative, magnitude): (bool, u128)) {
+		match Word::try_from(magnitude) {
+			Ok(word) if self.is_negative == is_negative => self.magnitude += word,
+			Ok(word) if self.magnitude >= word => {
+				self.magnitude -= word;
+				self.normalize_sign();
+			},
+			_ => self.add_signed(is_negative, &UBigNumber::from_primitive(magnitude))
+		}
+	}
+
+	fn sub_primitive(&mut self, (is_negative, magnitude): (bool, u128)) {
+		self.add_primitive((!is_negative, magnitude))
+	}
+
+	fn mul_primitive(&mut self, (is_negative, magnitude): (bool, u128)) {
+		self.is_negative ^= is_negative;
+		self.magnitude *= magnitude;
+		self.normalize_sign();
+	}
+
+	/// Division rounds toward zero, just like `quotient_and_remainder`
+	fn div_primitive(&mut self, (is_negative, magnitude): (bool, u128)) {
+		self.is_negative ^= is_negative;
+		self.magnitude /= magnitude;
+		self.normalize_sign();
+	}
+
+	/// The remainder has the sign of the dividend, just like `quotient_and_remainder`
+	fn rem_primitive(&mut self, (_, magnitude): (bool, u128)) {
+		self.magnitude %= magnitude;
+		self.normalize_sign();
+	}
+
+	fn cmp_primitive(&self, (is_negative, magnitude): (bool, u128)) -> Ordering {
+		match (self.is_negative, is_negative) {
+			(false, true) => Ordering::Greater,
+			(true, false) => Ordering::Less,
+			(false, false) => self.magnitude.partial_cmp(&magnitude).unwrap(),
+			(true, true) => self.magnitude.partial_cmp(&magnitude).unwrap().reverse()
+		}
+	}
+
+	/// The sign and magnitude of this number, if the magnitude fits in a `u128`
+	pub(crate) fn to_sign_magnitude(&self) -> Option<(bool, u128)> {
+		self.magnitude.to_sign_magnitude().map(|(_, magnitude)| (self.is_negative, magnitude))
+	}
+
+	// MARK: Two's Complement
+
+	/// The lowest `len` words of this number in two's complement. Every word past those is implicitly a copy of the
+	/// sign bit, so this has to be at least one word longer than the magnitude to hold the sign.
+	fn twos_complement_words(&self, len: usize) -> Vec<Word> {
+		let mut words = self.magnitude.words.clone();
+		words.resize(len, 0);
+		if self.is_negative {
+			negate_words(&mut words);
+		}
+		words
+	}
+
+	/// The number with the given two's complement words, where every word past those is all copies of the sign bit
+	fn from_twos_complement_words(mut words: Vec<Word>, is_negative: bool) -> BigNumber {
+		if is_negative {
+			negate_words(&mut words);
+		}
+		BigNumber::from_sign_magnitude(is_negative, UBigNumber::from_words(words))
+	}
+
+	/// Applies a bitwise operation to the two's complement words of this number and `rhs`. The words past the end
+	/// of both numbers are just their signs, so it's enough to go one word past the longer magnitude and then apply
+	/// the operation to the signs to get the sign of the result.
+	fn bitwise_assign(&mut self, rhs: &BigNumber, op: impl Fn(Word, Word) -> Word) {
+		let len = self.magnitude.len().max(rhs.magnitude.len()) + 1;
+		let words = self.twos_complement_words(len).into_iter()
+			.zip(rhs.twos_complement_words(len))
+			.map(|(a, b)| op(a, b))
+			.collect();
+
+		let sign_word = |x: &BigNumber| if x.is_negative { Word::MAX } else { 0 };
+		let is_negative = op(sign_word(self), sign_word(rhs)) != 0;
+
+		*self = BigNumber::from_twos_complement_words(words, is_negative);
+	}
+
+	/// Multiplies this number by 2^n in place
+	fn shift_left(&mut self, n: u64) {
+		self.magnitude <<= n;
+	}
+
+	/// Divides this number by 2^n in place, rounding toward negative infinity like `div_euclid` would
+	fn shift_right(&mut self, n: u64) {
+		if self.is_negative {
+			// -m >> n == -((m - 1) >> n) - 1
+			self.magnitude -= 1u8;
+			self.magnitude >>= n;
+			self.magnitude += 1u8;
+		} else {
+			self.magnitude >>= n;
+		}
+	}
+
+	/// Makes sure zero is never negative
+	fn normalize_sign(&mut self) {
+		if self.magnitude.is_zero() {
+			self.is_negative = false;
+		}
+	}
+
 	/// Computes the euclidean remainder when dividing by something.
 	/// This essentiall the "modulo" operation as it's commonly thought of in algebra,
 	/// where the remainder is always nonnegative.
 	pub fn euc_rem(&self, divisor: BigNumber) -> BigNumber {
-		let rem = self.clone() % divisor.clone();
+		let rem = self % &divisor;
 		if rem.is_negative {
 			rem + divisor
 		} else {
@@ -44,12 +179,31 @@ impl From<UBigNumber> for BigNumber {
 	}
 }
 
-impl Into<UBigNumber> for BigNumber {
-	fn into(self) -> UBigNumber {
-		if self.is_negative {
-			panic!("Cannot coerce negative interger into unsigned integer")
+forward_from_primitive!(impl for BigNumber, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+forward_try_into_primitive!(impl for BigNumber, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl TryFrom<BigNumber> for UBigNumber {
+	type Error = OutOfRangeError;
+
+	/// Fails if the number is negative
+	fn try_from(value: BigNumber) -> Result<Self, Self::Error> {
+		if value.is_negative {
+			Err(OutOfRangeError::new())
+		} else {
+			Ok(value.magnitude)
+		}
+	}
+}
+
+impl TryFrom<&BigNumber> for UBigNumber {
+	type Error = OutOfRangeError;
+
+	/// Fails if the number is negative
+	fn try_from(value: &BigNumber) -> Result<Self, Self::Error> {
+		if value.is_negative {
+			Err(OutOfRangeError::new())
 		} else {
-			self.magnitude
+			Ok(value.magnitude.clone())
 		}
 	}
 }
@@ -99,6 +253,13 @@ impl PartialOrd for BigNumber {
 
 // MARK: Arithmetic
 
+forward_binop!(impl commutative Add, add, AddAssign, add_assign for BigNumber);
+forward_binop!(impl Sub, sub, SubAssign, sub_assign for BigNumber);
+forward_binop!(impl commutative Mul, mul, MulAssign, mul_assign for BigNumber);
+forward_binop!(impl Div, div, DivAssign, div_assign for BigNumber);
+forward_binop!(impl Rem, rem, RemAssign, rem_assign for BigNumber);
+forward_primitive_ops!(impl for BigNumber, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
 impl Neg for BigNumber {
 	type Output = BigNumber;
 
@@ -107,103 +268,91 @@ impl Neg for BigNumber {
 	}
 }
 
-impl Add for BigNumber {
+impl Neg for &BigNumber {
 	type Output = BigNumber;
 
-	fn add(self, rhs: Self) -> Self::Output {
-		if self.is_negative == rhs.is_negative {
-			BigNumber::from_sign_magnitude(self.is_negative, self.magnitude + rhs.magnitude)
-		} else {
-			// reorder so that rhs is negative, and then we do subtraction with two positive numbers
-			if self.is_negative {
-				rhs.sub(-self)
-			} else {
-				self.sub(-rhs)
-			}
-		}
+	fn neg(self) -> Self::Output {
+		BigNumber::from_sign_magnitude(!self.is_negative, self.magnitude.clone())
 	}
 }
 
-impl AddAssign for BigNumber {
-	fn add_assign(&mut self, rhs: Self) {
-		*self = self.clone() + rhs
+impl AddAssign<&BigNumber> for BigNumber {
+	fn add_assign(&mut self, rhs: &BigNumber) {
+		self.add_signed(rhs.is_negative, &rhs.magnitude)
 	}
 }
 
-impl Sub for BigNumber {
-	type Output = BigNumber;
-
-	fn sub(self, rhs: Self) -> Self::Output {
-		if !self.is_negative && !rhs.is_negative {
-			if self > rhs {
-				BigNumber::from_sign_magnitude(self.is_negative, self.magnitude - rhs.magnitude)
-			} else if self == rhs {
-				BigNumber::zero()
-			} else {
-				BigNumber::from_sign_magnitude(true, rhs.magnitude - self.magnitude)
-			}
-		} else if !self.is_negative && rhs.is_negative {
-			self.add(-rhs)
-		} else if self.is_negative && !rhs.is_negative {
-			BigNumber::from_sign_magnitude(true, self.magnitude + rhs.magnitude)
-		} else {
-			// self is negative, rhs is negative. We should swap them so we have
-			//		-self - (-rhs) = -self + rhs = rhs - self
-			// this reverts to the first case
-			rhs.sub(-self)
-		}
+impl SubAssign<&BigNumber> for BigNumber {
+	fn sub_assign(&mut self, rhs: &BigNumber) {
+		self.add_signed(!rhs.is_negative, &rhs.magnitude)
 	}
 }
 
-impl SubAssign for BigNumber {
-	fn sub_assign(&mut self, rhs: Self) {
-		*self = self.clone() - rhs;
+impl MulAssign<&BigNumber> for BigNumber {
+	fn mul_assign(&mut self, rhs: &BigNumber) {
+		self.is_negative ^= rhs.is_negative;
+		self.magnitude *= &rhs.magnitude;
+		self.normalize_sign();
 	}
 }
 
-impl Mul for BigNumber {
-	type Output = BigNumber;
-
-	fn mul(self, rhs: Self) -> Self::Output {
-		BigNumber::from_sign_magnitude(self.is_negative ^ rhs.is_negative, self.magnitude * rhs.magnitude)
+impl DivAssign<&BigNumber> for BigNumber {
+	fn div_assign(&mut self, rhs: &BigNumber) {
+		let (quotient, _) = self.quotient_and_remainder(rhs);
+		*self = quotient;
 	}
 }
 
-impl MulAssign for BigNumber {
-	fn mul_assign(&mut self, rhs: Self) {
-		*self = self.clone() * rhs
+impl RemAssign<&BigNumber> for BigNumber {
+	fn rem_assign(&mut self, rhs: &BigNumber) {
+		let (_, remainder) = self.quotient_and_remainder(rhs);
+		*self = remainder;
 	}
 }
 
-impl Div for BigNumber {
+// MARK: Bitwise Operations
+// 
+// These all act like the number is written in two's complement with infinitely many copies of the sign bit on the
+// left, the same as Python's integers and GMP.
+
+forward_binop!(impl commutative BitAnd, bitand, BitAndAssign, bitand_assign for BigNumber);
+forward_binop!(impl commutative BitOr, bitor, BitOrAssign, bitor_assign for BigNumber);
+forward_binop!(impl commutative BitXor, bitxor, BitXorAssign, bitxor_assign for BigNumber);
+forward_shifts!(impl for BigNumber, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Not for BigNumber {
 	type Output = BigNumber;
 
-	fn div(self, rhs: Self) -> Self::Output {
-		match self.quotient_and_remainder(&rhs) {
-			(q, _) => q
-		}
+	/// `!x == -x - 1`
+	fn not(self) -> Self::Output {
+		-(self + 1u8)
 	}
 }
 
-impl DivAssign for BigNumber {
-	fn div_assign(&mut self, rhs: Self) {
-		*self = self.clone() / rhs
+impl Not for &BigNumber {
+	type Output = BigNumber;
+
+	/// `!x == -x - 1`
+	fn not(self) -> Self::Output {
+		-(self + 1u8)
 	}
 }
 
-impl Rem for BigNumber {
-	type Output = BigNumber;
+impl BitAndAssign<&BigNumber> for BigNumber {
+	fn bitand_assign(&mut self, rhs: &BigNumber) {
+		self.bitwise_assign(rhs, |a, b| a & b)
+	}
+}
 
-	fn rem(self, rhs: Self) -> Self::Output {
-		match self.quotient_and_remainder(&rhs) {
-			(_, r) => r
-		}
+impl BitOrAssign<&BigNumber> for BigNumber {
+	fn bitor_assign(&mut self, rhs: &BigNumber) {
+		self.bitwise_assign(rhs, |a, b| a | b)
 	}
 }
 
-impl RemAssign for BigNumber {
-	fn rem_assign(&mut self, rhs: Self) {
-		*self = self.clone() % rhs
+impl BitXorAssign<&BigNumber> for BigNumber {
+	fn bitxor_assign(&mut self, rhs: &BigNumber) {
+		self.bitwise_assign(rhs, |a, b| a ^ b)
 	}
 }
 
@@ -225,10 +374,8 @@ impl Ring for BigNumber {
 	fn power(&self, n: i64) -> Self {
 		if n < 0 {
 			panic!("Cannot invert integer")
-		} else if n == 0 {
-			BigNumber::one()
 		} else {
-			todo!()
+			self.pow(n as u64)
 		}
 	}
 }
@@ -247,4 +394,12 @@ impl EuclideanDomain for BigNumber {
 			BigNumber::from_sign_magnitude(self.is_negative, u_r)
 		)
 	}
-}
\ No newline at end of file
+}
+
+/// Negates a two's complement number in place, by flipping every bit and adding one
+fn negate_words(words: &mut [Word]) {
+	let mut carry = true;
+	for w in words.iter_mut() {
+		(*w, carry) = (!*w).overflowing_add(carry as Word);
+	}
+}
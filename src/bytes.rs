@@ -0,0 +1,146 @@
+//! Converting big numbers to and from raw bytes, for talking to files and network protocols.
+//! 
+//! Unsigned numbers are just their magnitude in bytes. Signed numbers are in two's complement, so the top bit of the
+//! most significant byte is the sign.
+
+use crate::{bignumber::BigNumber, error::OutOfRangeError, ubignumber::{UBigNumber, Word, WORD_BYTE_COUNT}};
+
+impl UBigNumber {
+
+	/// Creates a UBigNumber from its bytes, most significant first
+	pub fn from_bytes_be(bytes: &[u8]) -> UBigNumber {
+		UBigNumber::from_words(bytes.rchunks(WORD_BYTE_COUNT).map(|chunk| {
+			chunk.iter().fold(0, |word, b| (word << 8) | *b as Word)
+		}).collect())
+	}
+
+	/// Creates a UBigNumber from its bytes, least significant first
+	pub fn from_bytes_le(bytes: &[u8]) -> UBigNumber {
+		UBigNumber::from_words(bytes.chunks(WORD_BYTE_COUNT).map(|chunk| {
+			chunk.iter().rev().fold(0, |word, b| (word << 8) | *b as Word)
+		}).collect())
+	}
+
+	/// The bytes of this number, most significant first, with no leading zeroes (except for zero itself, which is
+	/// one zero byte)
+	pub fn to_bytes_be(&self) -> Vec<u8> {
+		let mut bytes = self.to_bytes_le();
+		bytes.reverse();
+		bytes
+	}
+
+	/// The bytes of this number, least significant first, with no trailing zeroes (except for zero itself, which is
+	/// one zero byte)
+	pub fn to_bytes_le(&self) -> Vec<u8> {
+		let mut bytes: Vec<u8> = self.words.iter().flat_map(|w| w.to_le_bytes()).collect();
+		while bytes.len() > 1 && bytes[bytes.len() - 1] == 0 {
+			bytes.pop();
+		}
+		if bytes.is_empty() {
+			bytes.push(0);
+		}
+		bytes
+	}
+
+	/// The bytes of this number, most significant first, padded with zeroes to exactly `len` bytes, or an error if
+	/// the number needs more than `len` bytes
+	pub fn to_bytes_be_padded(&self, len: usize) -> Result<Vec<u8>, OutOfRangeError> {
+		let mut bytes = self.to_bytes_le_padded(len)?;
+		bytes.reverse();
+		Ok(bytes)
+	}
+
+	/// The bytes of this number, least significant first, padded with zeroes to exactly `len` bytes, or an error if
+	/// the number needs more than `len` bytes
+	pub fn to_bytes_le_padded(&self, len: usize) -> Result<Vec<u8>, OutOfRangeError> {
+		let mut bytes = self.to_bytes_le();
+		if self.is_zero() {
+			bytes.clear();
+		}
+		if bytes.len() > len {
+			return Err(OutOfRangeError::new());
+		}
+		bytes.resize(len, 0);
+		Ok(bytes)
+	}
+}
+
+impl BigNumber {
+
+	/// Creates a BigNumber from its two's complement bytes, most significant first. No bytes at all is zero.
+	pub fn from_signed_bytes_be(bytes: &[u8]) -> BigNumber {
+		let mut le = bytes.to_vec();
+		le.reverse();
+		BigNumber::from_signed_bytes_le(&le)
+	}
+
+	/// Creates a BigNumber from its two's complement bytes, least significant first. No bytes at all is zero.
+	pub fn from_signed_bytes_le(bytes: &[u8]) -> BigNumber {
+		match bytes.last() {
+			Some(top) if top & 0x80 != 0 => {
+				let mut magnitude = bytes.to_vec();
+				negate_bytes(&mut magnitude);
+				BigNumber::from_sign_magnitude(true, UBigNumber::from_bytes_le(&magnitude))
+			},
+			_ => BigNumber::from_ubn(UBigNumber::from_bytes_le(bytes)),
+		}
+	}
+
+	/// The shortest two's complement bytes of this number, most significant first
+	pub fn to_signed_bytes_be(&self) -> Vec<u8> {
+		let mut bytes = self.to_signed_bytes_le();
+		bytes.reverse();
+		bytes
+	}
+
+	/// The shortest two's complement bytes of this number, least significant first
+	pub fn to_signed_bytes_le(&self) -> Vec<u8> {
+		// one more byte than the magnitude needs always has room for the sign bit
+		let mut bytes = self.magnitude.to_bytes_le();
+		bytes.push(0);
+		if self.is_negative {
+			negate_bytes(&mut bytes);
+		}
+
+		// Drop top bytes that are nothing but the sign extension of the byte below
+		while bytes.len() > 1 {
+			let (top, next) = (bytes[bytes.len() - 1], bytes[bytes.len() - 2]);
+			if (top == 0 && next & 0x80 == 0) || (top == 0xff && next & 0x80 != 0) {
+				bytes.pop();
+			} else {
+				break;
+			}
+		}
+		bytes
+	}
+
+	/// The two's complement bytes of this number, most significant first, sign extended to exactly `len` bytes, or
+	/// an error if the number doesn't fit in `len` bytes
+	pub fn to_signed_bytes_be_padded(&self, len: usize) -> Result<Vec<u8>, OutOfRangeError> {
+		let mut bytes = self.to_signed_bytes_le_padded(len)?;
+		bytes.reverse();
+		Ok(bytes)
+	}
+
+	/// The two's complement bytes of this number, least significant first, sign extended to exactly `len` bytes, or
+	/// an error if the number doesn't fit in `len` bytes
+	pub fn to_signed_bytes_le_padded(&self, len: usize) -> Result<Vec<u8>, OutOfRangeError> {
+		let mut bytes = self.to_signed_bytes_le();
+		if self.magnitude.is_zero() {
+			bytes.clear();
+		}
+		if bytes.len() > len {
+			return Err(OutOfRangeError::new());
+		}
+		bytes.resize(len, if self.is_negative { 0xff } else { 0 });
+		Ok(bytes)
+	}
+}
+
+/// Negates a little-endian two's complement number in place, by flipping every bit and adding one
+fn negate_bytes(bytes: &mut [u8]) {
+	let mut carry = true;
+	for b in bytes.iter_mut() {
+		(*b, carry) = (!*b).overflowing_add(carry as u8);
+	}
+}
@@ -0,0 +1,66 @@
+//! The errors that can come up when converting to and from big numbers.
+
+use std::{error::Error, fmt::Display};
+
+/// The error returned when a string can't be parsed as a big number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigNumberError {
+	kind: ParseBigNumberErrorKind,
+}
+
+/// What exactly went wrong when parsing a big number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseBigNumberErrorKind {
+
+	/// There were no digits to parse
+	Empty,
+
+	/// A character that isn't a digit in the radix being parsed, at the given byte offset into the string
+	InvalidDigit { position: usize, digit: char },
+
+	/// The string started with `0` and a letter, but the letter isn't one of the `x`, `o` or `b` radix prefixes
+	InvalidRadixPrefix,
+}
+
+impl ParseBigNumberError {
+
+	pub(crate) fn new(kind: ParseBigNumberErrorKind) -> ParseBigNumberError {
+		ParseBigNumberError { kind }
+	}
+
+	/// What went wrong
+	pub fn kind(&self) -> &ParseBigNumberErrorKind {
+		&self.kind
+	}
+}
+
+impl Display for ParseBigNumberError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.kind {
+			ParseBigNumberErrorKind::Empty => write!(f, "cannot parse a big number from a string with no digits"),
+			ParseBigNumberErrorKind::InvalidDigit { position, digit } => write!(f, "invalid digit {:?} at position {}", digit, position),
+			ParseBigNumberErrorKind::InvalidRadixPrefix => write!(f, "invalid radix prefix"),
+		}
+	}
+}
+
+impl Error for ParseBigNumberError {}
+
+/// The error returned when a big number doesn't fit in the type (or number of bytes) it's being converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRangeError(());
+
+impl OutOfRangeError {
+
+	pub(crate) fn new() -> OutOfRangeError {
+		OutOfRangeError(())
+	}
+}
+
+impl Display for OutOfRangeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "big number out of range for the target type")
+	}
+}
+
+impl Error for OutOfRangeError {}
@@ -0,0 +1,119 @@
+//! Converting big numbers to and from floating point numbers.
+
+use crate::{bignumber::BigNumber, error::OutOfRangeError, ubignumber::{UBigNumber, Word, WORD_BIT_COUNT}};
+
+/// The number of bits after the point in an `f64`'s significand
+const F64_FRACTION_BITS: u32 = 52;
+
+/// What's subtracted from an `f64`'s stored exponent to get the power of two it stands for
+const F64_EXPONENT_BIAS: i64 = 1023;
+
+impl UBigNumber {
+
+	/// The closest `f64` to this number, rounding ties to even, or infinity if it's too big to be an `f64`
+	pub fn to_f64(&self) -> f64 {
+		let (top, exponent) = self.top_word_sticky();
+		scale_f64(top as f64, exponent)
+	}
+
+	/// The closest `f32` to this number, rounding ties to even, or infinity if it's too big to be an `f32`
+	pub fn to_f32(&self) -> f32 {
+		// Once the top word is rounded to an f32, scaling it by a power of two is exact, so this only rounds once
+		let (top, exponent) = self.top_word_sticky();
+		scale_f64(top as f32 as f64, exponent) as f32
+	}
+
+	/// The 64 most significant bits of this number and the power of two they need to be scaled by. If any of the
+	/// bits below those are set, the lowest bit of the word is set too, so it still rounds the same way as the whole
+	/// number would (there are always more than two bits between the lowest bit and the rounding position).
+	fn top_word_sticky(&self) -> (Word, i64) {
+		let bit_len = self.bit_length() as usize;
+		if bit_len <= WORD_BIT_COUNT {
+			return (self.msw(), 0);
+		}
+
+		let shift = bit_len - WORD_BIT_COUNT;
+		let (word, bits) = (shift / WORD_BIT_COUNT, (shift % WORD_BIT_COUNT) as u32);
+
+		let top = if bits == 0 {
+			self[word]
+		} else {
+			(self[word] >> bits) | (self[word + 1] << (WORD_BIT_COUNT as u32 - bits))
+		};
+		let sticky = self.words[..word].iter().any(|w| *w != 0) || self[word] & ((1 << bits) - 1) != 0;
+
+		(top | sticky as Word, shift as i64)
+	}
+}
+
+impl BigNumber {
+
+	/// The closest `f64` to this number, rounding ties to even, or an infinity if it's too big to be an `f64`
+	pub fn to_f64(&self) -> f64 {
+		let magnitude = self.magnitude.to_f64();
+		if self.is_negative { -magnitude } else { magnitude }
+	}
+
+	/// The closest `f32` to this number, rounding ties to even, or an infinity if it's too big to be an `f32`
+	pub fn to_f32(&self) -> f32 {
+		let magnitude = self.magnitude.to_f32();
+		if self.is_negative { -magnitude } else { magnitude }
+	}
+}
+
+/// `value * 2^exponent`, which is exact unless it overflows to infinity
+fn scale_f64(value: f64, exponent: i64) -> f64 {
+	if exponent > F64_EXPONENT_BIAS {
+		if value == 0.0 { 0.0 } else { f64::INFINITY }
+	} else {
+		value * f64::from_bits(((exponent + F64_EXPONENT_BIAS) as u64) << F64_FRACTION_BITS)
+	}
+}
+
+/// The sign and magnitude of a float with its fraction truncated, or an error if it isn't finite
+fn truncate_f64(value: f64) -> Result<(bool, UBigNumber), OutOfRangeError> {
+	if !value.is_finite() {
+		return Err(OutOfRangeError::new());
+	}
+
+	let bits = value.to_bits();
+	let biased_exponent = ((bits >> F64_FRACTION_BITS) & 0x7ff) as i64;
+	if biased_exponent < F64_EXPONENT_BIAS {
+		// less than one, including all of the subnormals
+		return Ok((value.is_sign_negative(), UBigNumber::zero()));
+	}
+
+	// The value is significand * 2^shift, where the significand includes the implicit leading one
+	let significand = (bits & ((1 << F64_FRACTION_BITS) - 1)) | (1 << F64_FRACTION_BITS);
+	let shift = biased_exponent - F64_EXPONENT_BIAS - F64_FRACTION_BITS as i64;
+
+	let magnitude = if shift < 0 {
+		UBigNumber::from(significand >> -shift)
+	} else {
+		UBigNumber::from(significand) << shift
+	};
+
+	Ok((value.is_sign_negative(), magnitude))
+}
+
+impl TryFrom<f64> for UBigNumber {
+	type Error = OutOfRangeError;
+
+	/// Truncates a finite float toward zero, which fails if it's negative and at most -1
+	fn try_from(value: f64) -> Result<Self, Self::Error> {
+		match truncate_f64(value)? {
+			(true, magnitude) if !magnitude.is_zero() => Err(OutOfRangeError::new()),
+			(_, magnitude) => Ok(magnitude),
+		}
+	}
+}
+
+impl TryFrom<f64> for BigNumber {
+	type Error = OutOfRangeError;
+
+	/// Truncates a finite float toward zero
+	fn try_from(value: f64) -> Result<Self, Self::Error> {
+		let (is_negative, magnitude) = truncate_f64(value)?;
+		Ok(BigNumber::from_sign_magnitude(is_negative, magnitude))
+	}
+}
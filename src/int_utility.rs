@@ -1,5 +1,35 @@
+use std::cmp::{max, min, Ordering};
+
 use crate::ubignumber::{self, Word};
 
+/// The size (in words) of the smaller operand below which schoolbook multiplication is faster than Karatsuba.
+/// 
+/// See `benches/multiplication.rs`. On x86_64 one level of Karatsuba breaks even with schoolbook at around 80 words.
+pub const KARATSUBA_THRESHOLD: usize = 80;
+
+/// The size (in words) of the smaller operand above which Toom-3 is faster than Karatsuba.
+/// 
+/// See `benches/multiplication.rs`. On x86_64 one level of Toom-3 breaks even with Karatsuba at around 600 words.
+pub const TOOM_3_THRESHOLD: usize = 600;
+
+/// The size (in words) of the smaller operand above which Toom-4 is faster than Toom-3.
+/// 
+/// See `benches/multiplication.rs`. On x86_64 one level of Toom-4 breaks even with Toom-3 at around 2000 words.
+pub const TOOM_4_THRESHOLD: usize = 2000;
+
+/// The size (in words) of the smaller operand above which NTT multiplication is faster than Toom-4.
+/// 
+/// See `benches/multiplication.rs`. On x86_64 NTT multiplication is consistently faster than Toom-4 from around 30000
+/// words. Below that it depends a lot on how much padding the transform length needs.
+pub const NTT_THRESHOLD: usize = 30000;
+
+/// The size (in words) of the divisor, and of the dividend beyond the divisor, above which Burnikel-Ziegler
+/// division is faster than Knuth's Algorithm D.
+/// 
+/// This is also the size below which the recursion bottoms out. Timing 2n-by-n word divisions on x86_64 with
+/// thresholds of 40, 80 and 160 words, 80 was the fastest for every size from 100 to 1600 words.
+pub const BURNIKEL_ZIEGLER_THRESHOLD: usize = 80;
+
 /// Computes the 128-bit result of the operation `a*b + c + d`
 /// 
 /// This calls compiler intrinsic commands which just call processor instructions or whatever
@@ -12,6 +42,50 @@ pub fn addmul(a: Word, b: Word, c: Word, d: Word) -> (Word, Word) {
 
 /// Computes wide multiplication
 pub fn word_mul(lhs: Vec<Word>, rhs: Vec<Word>) -> Vec<Word> {
+	schoolbook_mul(&lhs, &rhs)
+}
+
+/// Computes wide multiplication, picking the fastest algorithm for the sizes of the operands.
+/// 
+/// The result has exactly `lhs.len() + rhs.len()` words, so it may not be normalized.
+pub fn mul(lhs: &[Word], rhs: &[Word]) -> Vec<Word> {
+	let (long, short) = if lhs.len() >= rhs.len() { (lhs, rhs) } else { (rhs, lhs) };
+
+	if short.len() < KARATSUBA_THRESHOLD {
+		schoolbook_mul(lhs, rhs)
+	} else if 2 * short.len() <= long.len() {
+		unbalanced_mul(long, short)
+	} else if short.len() < TOOM_3_THRESHOLD {
+		karatsuba_mul(lhs, rhs)
+	} else if short.len() < TOOM_4_THRESHOLD {
+		toom_3_mul(lhs, rhs)
+	} else if short.len() < NTT_THRESHOLD {
+		toom_4_mul(lhs, rhs)
+	} else {
+		ntt_mul(lhs, rhs)
+	}
+}
+
+/// Computes the square of a number given as words, picking the fastest algorithm for its size.
+/// 
+/// This uses the same thresholds as `mul`. Each tier squares its pieces recursively, so the savings of squaring
+/// carry through every level.
+pub fn square(words: &[Word]) -> Vec<Word> {
+	if words.len() < KARATSUBA_THRESHOLD {
+		schoolbook_square(words)
+	} else if words.len() < TOOM_3_THRESHOLD {
+		karatsuba_square(words)
+	} else if words.len() < TOOM_4_THRESHOLD {
+		toom_3_square(words)
+	} else if words.len() < NTT_THRESHOLD {
+		toom_4_square(words)
+	} else {
+		ntt_square(words)
+	}
+}
+
+/// The O(n*m) multiplication algorithm we all learned in grade school
+fn schoolbook_mul(lhs: &[Word], rhs: &[Word]) -> Vec<Word> {
 	let mut product_words = vec![0 ; lhs.len() + rhs.len()];
 
 	for j in 0..rhs.len() {
@@ -27,6 +101,767 @@ pub fn word_mul(lhs: Vec<Word>, rhs: Vec<Word>) -> Vec<Word> {
 	product_words
 }
 
+/// Schoolbook squaring. Each cross product `a_i * a_j` shows up twice in the square, so we only compute the ones
+/// with `i < j`, double them all at once with a shift, and then add in the squares `a_i * a_i`.
+fn schoolbook_square(words: &[Word]) -> Vec<Word> {
+	let mut square_words = vec![0 ; 2 * words.len()];
+	schoolbook_square_into(words, &mut square_words);
+	square_words
+}
+
+/// Schoolbook squaring into `square_words`, which must be exactly twice as long as `words`
+fn schoolbook_square_into(words: &[Word], square_words: &mut [Word]) {
+	let n = words.len();
+	debug_assert!(square_words.len() == 2 * n);
+	square_words.fill(0);
+
+	for i in 0..n {
+		let mut carry = 0;
+
+		for j in (i + 1)..n {
+			(square_words[i + j], carry) = addmul(words[i], words[j], carry, square_words[i + j])
+		}
+
+		if i + 1 < n {
+			square_words[i + n] = carry
+		}
+	}
+
+	let mut shifted_out = 0;
+	for w in square_words.iter_mut() {
+		(*w, shifted_out) = ((*w << 1) | shifted_out, *w >> (ubignumber::WORD_BIT_COUNT - 1));
+	}
+
+	let mut carry = false;
+	for i in 0..n {
+		let (lo, hi) = words[i].carrying_mul(words[i], 0);
+		(square_words[2 * i], carry) = square_words[2 * i].carrying_add(lo, carry);
+		(square_words[2 * i + 1], carry) = square_words[2 * i + 1].carrying_add(hi, carry);
+	}
+}
+
+/// Karatsuba multiplication, which trades one of the four half-size multiplications for a few additions.
+/// 
+/// Splitting both operands at `half` words as `a = a1*B^half + a0` and `b = b1*B^half + b0`, we have
+///     a*b = a1*b1*B^(2*half) + ((a0 + a1)(b0 + b1) - a0*b0 - a1*b1)*B^half + a0*b0
+/// 
+/// Both operands need to be longer than `half`, which `mul` makes sure of by only calling this on operands of
+/// similar size.
+pub fn karatsuba_mul(lhs: &[Word], rhs: &[Word]) -> Vec<Word> {
+	let half = max(lhs.len(), rhs.len()) / 2;
+	debug_assert!(lhs.len() > half && rhs.len() > half);
+
+	let (a0, a1) = lhs.split_at(half);
+	let (b0, b1) = rhs.split_at(half);
+
+	let z0 = mul(a0, b0);
+	let z2 = mul(a1, b1);
+
+	let mut z1 = mul(&add_words(a0, a1), &add_words(b0, b1));
+	let borrow_0 = sub_assign_words(&mut z1, &z0);
+	let borrow_2 = sub_assign_words(&mut z1, &z2);
+	debug_assert!(!borrow_0 && !borrow_2);
+
+	let mut product_words = vec![0 ; lhs.len() + rhs.len()];
+	add_assign_words_at(&mut product_words, trimmed(&z0), 0);
+	add_assign_words_at(&mut product_words, trimmed(&z2), 2 * half);
+	add_assign_words_at(&mut product_words, trimmed(&z1), half);
+
+	product_words
+}
+
+/// Karatsuba squaring, which needs three half-size squares instead of four half-size products.
+pub fn karatsuba_square(words: &[Word]) -> Vec<Word> {
+	let half = words.len() / 2;
+	let (a0, a1) = words.split_at(half);
+
+	let z0 = square(a0);
+	let z2 = square(a1);
+
+	let mut z1 = square(&add_words(a0, a1));
+	let borrow_0 = sub_assign_words(&mut z1, &z0);
+	let borrow_2 = sub_assign_words(&mut z1, &z2);
+	debug_assert!(!borrow_0 && !borrow_2);
+
+	let mut square_words = vec![0 ; 2 * words.len()];
+	add_assign_words_at(&mut square_words, trimmed(&z0), 0);
+	add_assign_words_at(&mut square_words, trimmed(&z2), 2 * half);
+	add_assign_words_at(&mut square_words, trimmed(&z1), half);
+
+	square_words
+}
+
+/// Multiplies operands of very different sizes by cutting the longer one into pieces the size of the shorter one,
+/// so that each partial product is balanced and can use the fast algorithms.
+fn unbalanced_mul(long: &[Word], short: &[Word]) -> Vec<Word> {
+	let mut product_words = vec![0 ; long.len() + short.len()];
+
+	for (i, chunk) in long.chunks(short.len()).enumerate() {
+		let partial_product = mul(chunk, short);
+		add_assign_words_at(&mut product_words, trimmed(&partial_product), i * short.len());
+	}
+
+	product_words
+}
+
+// MARK: Toom-Cook
+
+/// The finite points Toom-Cook evaluates at. Toom-k uses the first `2k - 2` of them, plus the point at infinity.
+const TOOM_POINTS: [i64; 6] = [0, 1, -1, 2, -2, 3];
+
+/// Toom-Cook 3-way multiplication, which splits each operand into three pieces and does five multiplications of
+/// a third of the size.
+pub fn toom_3_mul(lhs: &[Word], rhs: &[Word]) -> Vec<Word> {
+	toom_mul(lhs, Some(rhs), 3)
+}
+
+/// Toom-Cook 3-way squaring, which does five squares of a third of the size.
+pub fn toom_3_square(words: &[Word]) -> Vec<Word> {
+	toom_mul(words, None, 3)
+}
+
+/// Toom-Cook 4-way multiplication, which splits each operand into four pieces and does seven multiplications of
+/// a quarter of the size.
+pub fn toom_4_mul(lhs: &[Word], rhs: &[Word]) -> Vec<Word> {
+	toom_mul(lhs, Some(rhs), 4)
+}
+
+/// Toom-Cook 4-way squaring, which does seven squares of a quarter of the size.
+pub fn toom_4_square(words: &[Word]) -> Vec<Word> {
+	toom_mul(words, None, 4)
+}
+
+/// Toom-Cook k-way multiplication.
+/// 
+/// Both operands are thought of as polynomials of degree `k - 1` in `x = B^piece`. Their product is a polynomial
+/// of degree `2k - 2`, which we find by multiplying the values of the operands at `2k - 1` points and
+/// interpolating. The interpolation uses Newton's divided differences, which are all integers (so every division
+/// is exact) since the product polynomial has integer coefficients.
+/// 
+/// If `rhs` is `None`, this squares `lhs`, which only needs one evaluation per point and squares instead of products.
+fn toom_mul(lhs: &[Word], rhs: Option<&[Word]>, k: usize) -> Vec<Word> {
+	let rhs_len = rhs.map_or(lhs.len(), |r| r.len());
+	let piece = max(lhs.len(), rhs_len).div_ceil(k);
+	let points = &TOOM_POINTS[..(2 * k - 2)];
+
+	let lhs_pieces = toom_split(lhs, piece, k);
+	let rhs_pieces = rhs.map(|r| toom_split(r, piece, k));
+
+	// the point at infinity is just the product of the leading coefficients
+	let product_inf = SignedWords::from_words(&match &rhs_pieces {
+		Some(rhs_pieces) => mul(lhs_pieces[k - 1], rhs_pieces[k - 1]),
+		None => square(lhs_pieces[k - 1])
+	});
+
+	// The values of the product at each finite point, with the leading term taken out so that what's left has
+	// degree `2k - 3` and is determined by the finite points alone.
+	let mut values: Vec<SignedWords> = points.iter().map(|t| {
+		let lhs_value = toom_evaluate(&lhs_pieces, *t);
+		let product = match &rhs_pieces {
+			Some(rhs_pieces) => lhs_value.mul(&toom_evaluate(rhs_pieces, *t)),
+			None => lhs_value.square()
+		};
+		product.sub(&product_inf.mul_small(t.pow(2 * k as u32 - 2)))
+	}).collect();
+
+	// Newton's divided differences, in place
+	for j in 1..points.len() {
+		for i in (j..points.len()).rev() {
+			values[i] = values[i].sub(&values[i - 1]).div_exact_small(points[i] - points[i - j]);
+		}
+	}
+
+	// Expand the Newton form c_0 + (x - t_0)(c_1 + (x - t_1)(c_2 + ...)) back into ordinary coefficients
+	let mut coefficients = vec![values[points.len() - 1].clone()];
+	for i in (0..(points.len() - 1)).rev() {
+		let mut expanded = vec![SignedWords::zero() ; coefficients.len() + 1];
+		for (j, c) in coefficients.iter().enumerate() {
+			expanded[j + 1] = expanded[j + 1].add(c);
+			expanded[j] = expanded[j].sub(&c.mul_small(points[i]));
+		}
+		expanded[0] = expanded[0].add(&values[i]);
+		coefficients = expanded;
+	}
+	coefficients.push(product_inf);
+
+	let mut product_words = vec![0 ; lhs.len() + rhs_len];
+	for (i, c) in coefficients.iter().enumerate() {
+		debug_assert!(!c.negative || trimmed(&c.magnitude).is_empty());
+		add_assign_words_at(&mut product_words, trimmed(&c.magnitude), i * piece);
+	}
+
+	product_words
+}
+
+/// Splits `words` into `k` pieces of `piece` words, the last few of which may be shorter or even empty
+fn toom_split(words: &[Word], piece: usize, k: usize) -> Vec<&[Word]> {
+	(0..k).map(|i| &words[min(i * piece, words.len())..min((i + 1) * piece, words.len())]).collect()
+}
+
+/// Evaluates the polynomial with coefficients `pieces` at a small point using Horner's method
+fn toom_evaluate(pieces: &[&[Word]], point: i64) -> SignedWords {
+	let mut value = SignedWords::zero();
+	for p in pieces.iter().rev() {
+		value = value.mul_small(point).add(&SignedWords::from_words(p));
+	}
+	value
+}
+
+/// A signed integer as a sign and magnitude, for the intermediate values in Toom-Cook, which can go negative.
+#[derive(Clone)]
+struct SignedWords {
+	negative: bool,
+	magnitude: Vec<Word>
+}
+
+impl SignedWords {
+
+	fn zero() -> SignedWords {
+		SignedWords { negative: false, magnitude: vec![] }
+	}
+
+	fn from_words(words: &[Word]) -> SignedWords {
+		SignedWords { negative: false, magnitude: trimmed(words).to_vec() }
+	}
+
+	/// Makes a new one, making sure zero is never negative and the magnitude is trimmed
+	fn from_sign_magnitude(negative: bool, mut magnitude: Vec<Word>) -> SignedWords {
+		magnitude.truncate(trimmed(&magnitude).len());
+		SignedWords { negative: negative && !magnitude.is_empty(), magnitude }
+	}
+
+	fn add(&self, rhs: &SignedWords) -> SignedWords {
+		if self.negative == rhs.negative {
+			SignedWords::from_sign_magnitude(self.negative, add_words(&self.magnitude, &rhs.magnitude))
+		} else if cmp_words(&self.magnitude, &rhs.magnitude) == Ordering::Less {
+			let mut difference = rhs.magnitude.clone();
+			sub_assign_words(&mut difference, &self.magnitude);
+			SignedWords::from_sign_magnitude(rhs.negative, difference)
+		} else {
+			let mut difference = self.magnitude.clone();
+			sub_assign_words(&mut difference, &rhs.magnitude);
+			SignedWords::from_sign_magnitude(self.negative, difference)
+		}
+	}
+
+	fn sub(&self, rhs: &SignedWords) -> SignedWords {
+		self.add(&SignedWords { negative: !rhs.negative, magnitude: rhs.magnitude.clone() })
+	}
+
+	fn mul(&self, rhs: &SignedWords) -> SignedWords {
+		if self.magnitude.is_empty() || rhs.magnitude.is_empty() {
+			SignedWords::zero()
+		} else {
+			SignedWords::from_sign_magnitude(self.negative ^ rhs.negative, mul(&self.magnitude, &rhs.magnitude))
+		}
+	}
+
+	fn square(&self) -> SignedWords {
+		SignedWords::from_sign_magnitude(false, square(&self.magnitude))
+	}
+
+	fn mul_small(&self, n: i64) -> SignedWords {
+		let mut product = mul_word(&self.magnitude, n.unsigned_abs());
+		product.truncate(trimmed(&product).len());
+		SignedWords::from_sign_magnitude(self.negative ^ (n < 0), product)
+	}
+
+	/// Divides by a small number which is known to divide this one
+	fn div_exact_small(&self, n: i64) -> SignedWords {
+		let (quotient, remainder) = div_rem_word(&self.magnitude, n.unsigned_abs());
+		debug_assert_eq!(remainder, 0);
+		SignedWords::from_sign_magnitude(self.negative ^ (n < 0), quotient)
+	}
+}
+
+// MARK: Number Theoretic Transform
+
+/// Primes of the form `c * 2^k + 1` for large `k`, along with a primitive root of each. These are all below 2^62,
+/// and their product is about 2^183.7. Each coefficient of the convolution of two sequences of 64-bit words is at
+/// most `len * (2^64 - 1)^2`, so it can be put back together exactly as long as that's less than the product, which
+/// is what `NTT_MAX_CONVOLUTION_LEN` is.
+const NTT_PRIMES: [(Word, Word); 3] = [
+	(4179340454199820289, 3), // 29 * 2^57 + 1
+	(2485986994308513793, 5), // 69 * 2^55 + 1
+	(1945555039024054273, 5), // 27 * 2^56 + 1
+];
+
+/// The longest convolution (in words) that `NTT_PRIMES` can hold every coefficient of, which is
+/// `floor((p1 * p2 * p3 - 1) / (2^64 - 1)^2)`, or about 2^55.7.
+const NTT_MAX_CONVOLUTION_LEN: u64 = 59403314713853952;
+
+/// Multiplication using number theoretic transforms, in O(n log n) time.
+/// 
+/// The words of the operands are treated as the coefficients of polynomials, which are multiplied by convolution
+/// modulo each of the `NTT_PRIMES` using a number theoretic transform (an FFT over a finite field). The actual
+/// coefficients of the product are put back together with the Chinese remainder theorem, and carried into words.
+pub fn ntt_mul(lhs: &[Word], rhs: &[Word]) -> Vec<Word> {
+	ntt_convolve(lhs, Some(rhs))
+}
+
+/// Squaring using number theoretic transforms, which only needs to transform the input once per prime.
+pub fn ntt_square(words: &[Word]) -> Vec<Word> {
+	ntt_convolve(words, None)
+}
+
+/// Does the work of `ntt_mul`, or of `ntt_square` if `rhs` is `None`
+fn ntt_convolve(lhs: &[Word], rhs: Option<&[Word]>) -> Vec<Word> {
+	let rhs_len = rhs.map_or(lhs.len(), |r| r.len());
+	let mut product_words = vec![0 ; lhs.len() + rhs_len];
+	if lhs.is_empty() || rhs_len == 0 {
+		return product_words;
+	}
+
+	let convolution_len = lhs.len() + rhs_len - 1;
+	debug_assert!(convolution_len as u64 <= NTT_MAX_CONVOLUTION_LEN, "Too many words for the NTT primes to hold");
+	let transform_len = convolution_len.next_power_of_two();
+
+	let residues: Vec<Vec<Word>> = NTT_PRIMES.iter().map(|(modulus, root)| {
+		let field = NttField::new(*modulus, *root);
+		let twiddles = field.twiddles(transform_len);
+		let mut lhs_values = field.transform_words(lhs, &twiddles);
+
+		match rhs {
+			Some(rhs) => {
+				let rhs_values = field.transform_words(rhs, &twiddles);
+				for (l, r) in lhs_values.iter_mut().zip(rhs_values.iter()) {
+					*l = field.mul(*l, *r);
+				}
+			},
+			None => {
+				for l in lhs_values.iter_mut() {
+					*l = field.mul(*l, *l);
+				}
+			}
+		}
+
+		field.transform(&mut lhs_values, &twiddles, true);
+		lhs_values.iter().map(|v| field.to_normal(*v)).collect()
+	}).collect();
+
+	// Garner's algorithm to recombine the residues, then carry each coefficient into the product. The inverses are
+	// kept in Montgomery form so that a Montgomery multiplication by them is an ordinary modular multiplication.
+	let [p1, p2, p3] = NTT_PRIMES.map(|(modulus, _)| modulus);
+	let (field_2, field_3) = (NttField::new(p2, 0), NttField::new(p3, 0));
+	let p1_inv_p2 = field_2.pow(field_2.to_mont(p1), p2 - 2);
+	let p1_inv_p3 = field_3.pow(field_3.to_mont(p1), p3 - 2);
+	let p2_inv_p3 = field_3.pow(field_3.to_mont(p2), p3 - 2);
+	let p1_p2 = p1 as u128 * p2 as u128;
+
+	let mut carry = [0 as Word ; 3];
+
+	for (i, product_word) in product_words.iter_mut().enumerate() {
+		if i < convolution_len {
+			let (r1, r2, r3) = (residues[0][i], residues[1][i], residues[2][i]);
+			let v2 = field_2.mul(field_2.sub(r2, r1 % p2), p1_inv_p2);
+			let v3 = field_3.mul(field_3.sub(field_3.mul(field_3.sub(r3, r1 % p3), p1_inv_p3), v2 % p3), p2_inv_p3);
+
+			// coefficient = r1 + p1*v2 + p1*p2*v3
+			let low = r1 as u128 + p1 as u128 * v2 as u128;
+			let (w0, c) = addmul(p1_p2 as Word, v3, low as Word, 0);
+			let (w1, w2) = addmul((p1_p2 >> 64) as Word, v3, c, (low >> 64) as Word);
+
+			let mut overflow = false;
+			for (acc, w) in carry.iter_mut().zip([w0, w1, w2]) {
+				(*acc, overflow) = acc.carrying_add(w, overflow);
+			}
+			debug_assert!(!overflow);
+		}
+
+		*product_word = carry[0];
+		carry = [carry[1], carry[2], 0];
+	}
+
+	debug_assert_eq!(carry, [0, 0, 0]);
+
+	product_words
+}
+
+/// Arithmetic modulo a prime below 2^62, in Montgomery form so that no divisions are needed.
+struct NttField {
+	modulus: Word,
+
+	/// -modulus^-1 mod 2^64
+	neg_inv: Word,
+
+	/// 2^128 mod modulus, for converting into Montgomery form
+	r_squared: Word,
+
+	/// A primitive root of the field, in Montgomery form
+	root: Word
+}
+
+impl NttField {
+
+	fn new(modulus: Word, root: Word) -> NttField {
+		let r_squared = ((1u128 << 64) % modulus as u128 * ((1u128 << 64) % modulus as u128) % modulus as u128) as Word;
+		let mut field = NttField { modulus, neg_inv: word_inverse(modulus).wrapping_neg(), r_squared, root: 0 };
+		field.root = field.to_mont(root);
+		field
+	}
+
+	/// Computes `a * b / 2^64 mod modulus`, which is multiplication for numbers in Montgomery form
+	fn mul(&self, a: Word, b: Word) -> Word {
+		let product = a as u128 * b as u128;
+		let m = (product as Word).wrapping_mul(self.neg_inv);
+		let reduced = ((product + m as u128 * self.modulus as u128) >> 64) as Word;
+		if reduced >= self.modulus { reduced - self.modulus } else { reduced }
+	}
+
+	fn add(&self, a: Word, b: Word) -> Word {
+		let sum = a + b;
+		if sum >= self.modulus { sum - self.modulus } else { sum }
+	}
+
+	fn sub(&self, a: Word, b: Word) -> Word {
+		if a >= b { a - b } else { a + self.modulus - b }
+	}
+
+	fn pow(&self, base: Word, mut exp: Word) -> Word {
+		let mut result = self.to_mont(1);
+		let mut base = base;
+		while exp > 0 {
+			if exp & 1 == 1 {
+				result = self.mul(result, base);
+			}
+			base = self.mul(base, base);
+			exp >>= 1;
+		}
+		result
+	}
+
+	fn to_mont(&self, a: Word) -> Word {
+		self.mul(a % self.modulus, self.r_squared)
+	}
+
+	fn to_normal(&self, a: Word) -> Word {
+		self.mul(a, 1)
+	}
+
+	/// The powers of a primitive `len`-th root of unity, up to `len / 2`, which are all the twiddle factors a
+	/// transform of length `len` needs.
+	fn twiddles(&self, len: usize) -> Vec<Word> {
+		let step = self.pow(self.root, (self.modulus - 1) / len as Word);
+		let mut twiddles = Vec::with_capacity(len / 2);
+		twiddles.push(self.to_mont(1));
+		for k in 1..(len / 2) {
+			twiddles.push(self.mul(twiddles[k - 1], step));
+		}
+		twiddles
+	}
+
+	/// Reduces the words into Montgomery form, pads them with zeroes, and transforms them
+	fn transform_words(&self, words: &[Word], twiddles: &[Word]) -> Vec<Word> {
+		let mut values: Vec<Word> = words.iter().map(|w| self.to_mont(*w)).collect();
+		values.resize(2 * twiddles.len(), 0);
+		self.transform(&mut values, twiddles, false);
+		values
+	}
+
+	/// The iterative Cooley-Tukey transform, in place. The length must be a power of two, with `twiddles` made for
+	/// that length.
+	/// 
+	/// The inverse transform is the same as the forward one with the outputs (besides the first) in reverse order,
+	/// and then divided by the length.
+	fn transform(&self, values: &mut [Word], twiddles: &[Word], inverse: bool) {
+		let n = values.len();
+		debug_assert_eq!(n, 2 * twiddles.len());
+
+		// bit reversal permutation
+		let mut j = 0;
+		for i in 1..n {
+			let mut bit = n >> 1;
+			while j & bit != 0 {
+				j ^= bit;
+				bit >>= 1;
+			}
+			j ^= bit;
+			if i < j {
+				values.swap(i, j);
+			}
+		}
+
+		let mut len = 2;
+		while len <= n {
+			let stride = n / len;
+
+			for block in values.chunks_mut(len) {
+				let (lo, hi) = block.split_at_mut(len / 2);
+				for (k, (u, v)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+					let t = self.mul(*v, twiddles[k * stride]);
+					(*u, *v) = (self.add(*u, t), self.sub(*u, t));
+				}
+			}
+
+			len <<= 1;
+		}
+
+		if inverse {
+			values[1..].reverse();
+			let n_inv = self.pow(self.to_mont(n as Word), self.modulus - 2);
+			for v in values.iter_mut() {
+				*v = self.mul(*v, n_inv);
+			}
+		}
+	}
+}
+
+// MARK: Single Word Modular Arithmetic
+
+/// Computes `a^-1 mod 2^64` for an odd `a`, by Newton's iteration. Each step doubles the number of correct bits.
+pub fn word_inverse(a: Word) -> Word {
+	debug_assert!(a & 1 == 1);
+
+	// a*a = 1 mod 8 for any odd a, so this is correct to 3 bits
+	let mut inv = a;
+	for _ in 0..5 {
+		inv = inv.wrapping_mul((2 as Word).wrapping_sub(a.wrapping_mul(inv)));
+	}
+	inv
+}
+
+/// The greatest common divisor of two words, by Stein's binary GCD
+pub fn word_gcd(mut a: Word, mut b: Word) -> Word {
+	if a == 0 || b == 0 {
+		return a | b;
+	}
+
+	let common_zeros = (a | b).trailing_zeros();
+	a >>= a.trailing_zeros();
+
+	while b != 0 {
+		b >>= b.trailing_zeros();
+		if a > b {
+			std::mem::swap(&mut a, &mut b);
+		}
+		b -= a;
+	}
+
+	a << common_zeros
+}
+
+// MARK: Montgomery Multiplication
+
+/// Computes `lhs * rhs * R^-1 mod modulus` into `out`, where `R = B^n` and `n` is the length of the modulus, with the
+/// CIOS (coarsely integrated operand scanning) method from Koç, Acar and Kaliski's "Analyzing and Comparing Montgomery
+/// Multiplication Algorithms" (1996). 
+/// 
+/// `out` and both operands must be `n` words, the operands must be less than the modulus, which must be odd, and
+/// `neg_inv` must be `-modulus^-1 mod B`. The two words of the accumulator past `out` are kept on the side, so this
+/// never allocates.
+pub fn montgomery_mul(out: &mut [Word], lhs: &[Word], rhs: &[Word], modulus: &[Word], neg_inv: Word) {
+	let n = modulus.len();
+	debug_assert!(lhs.len() == n && rhs.len() == n && out.len() == n);
+
+	out.fill(0);
+	let mut top: Word = 0;
+
+	for &r in rhs {
+		// (out, top, overflow) += lhs * r
+		let mut carry = 0;
+		for j in 0..n {
+			(out[j], carry) = addmul(lhs[j], r, out[j], carry);
+		}
+		let (sum, overflow) = top.overflowing_add(carry);
+
+		// (out, sum, overflow) = ((out, sum, overflow) + m * modulus) / B, where m makes the lowest word zero
+		let m = out[0].wrapping_mul(neg_inv);
+		let (_, mut carry) = addmul(m, modulus[0], out[0], 0);
+		for j in 1..n {
+			(out[j - 1], carry) = addmul(m, modulus[j], out[j], carry);
+		}
+		let (sum, carried) = sum.overflowing_add(carry);
+		out[n - 1] = sum;
+		top = overflow as Word + carried as Word;
+	}
+
+	// The result is less than twice the modulus, so at most one subtraction brings it into range
+	if top != 0 || cmp_words(out, modulus) != Ordering::Less {
+		sub_assign_words(out, modulus);
+	}
+}
+
+/// Computes `x * x * R^-1 mod modulus` into `out`, with the same requirements as `montgomery_mul`.
+/// 
+/// Interleaving the reduction with the multiplication like CIOS does would mean computing every cross product twice,
+/// so this squares the whole number first with `schoolbook_square_into` and then reduces it one word at a time. The
+/// full square goes in `product`, which must be `2n` words, so this doesn't allocate either.
+pub fn montgomery_square(out: &mut [Word], x: &[Word], modulus: &[Word], neg_inv: Word, product: &mut [Word]) {
+	let n = modulus.len();
+	debug_assert!(x.len() == n && out.len() == n);
+
+	schoolbook_square_into(x, product);
+
+	// For each word, add m * modulus * B^i so that word becomes zero. The carries into the word past the top of
+	// product are kept in top, which is at most one.
+	let mut top: Word = 0;
+	for i in 0..n {
+		let m = product[i].wrapping_mul(neg_inv);
+		let mut carry = 0;
+		for j in 0..n {
+			(product[i + j], carry) = addmul(m, modulus[j], product[i + j], carry);
+		}
+		let (sum, overflow) = product[i + n].overflowing_add(carry);
+		let (sum, carried) = sum.overflowing_add(top);
+		product[i + n] = sum;
+		top = overflow as Word + carried as Word;
+	}
+
+	// The lowest n words are all zero now, so the top half is the division by R, and it's less than twice the modulus
+	out.copy_from_slice(&product[n..]);
+	if top != 0 || cmp_words(out, modulus) != Ordering::Less {
+		sub_assign_words(out, modulus);
+	}
+}
+
+// MARK: Word Slice Helpers
+
+/// Computes `lhs * rhs` for a single word `rhs`, with one extra word for the carry
+pub fn mul_word(lhs: &[Word], rhs: Word) -> Vec<Word> {
+	let mut product = Vec::with_capacity(lhs.len() + 1);
+	let mut carry = 0;
+
+	for w in lhs {
+		let (lo, hi) = addmul(*w, rhs, carry, 0);
+		product.push(lo);
+		carry = hi;
+	}
+
+	product.push(carry);
+	product
+}
+
+/// Computes the quotient and remainder of dividing by a single word
+pub fn div_rem_word(lhs: &[Word], rhs: Word) -> (Vec<Word>, Word) {
+	let mut quotient = vec![0 ; lhs.len()];
+	let mut remainder = 0;
+
+	for i in (0..lhs.len()).rev() {
+		(_, quotient[i], remainder) = div_wide(remainder, lhs[i], rhs);
+	}
+
+	(quotient, remainder)
+}
+
+/// Compares two numbers given as words, ignoring any extra zero words
+pub fn cmp_words(lhs: &[Word], rhs: &[Word]) -> Ordering {
+	let (lhs, rhs) = (trimmed(lhs), trimmed(rhs));
+	lhs.len().cmp(&rhs.len()).then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+/// The words with any most significant zero words cut off
+pub fn trimmed(words: &[Word]) -> &[Word] {
+	let len = words.iter().rposition(|w| *w != 0).map_or(0, |i| i + 1);
+	&words[..len]
+}
+
+/// Computes `lhs + rhs`, with one extra word to hold a possible carry
+pub fn add_words(lhs: &[Word], rhs: &[Word]) -> Vec<Word> {
+	let (long, short) = if lhs.len() >= rhs.len() { (lhs, rhs) } else { (rhs, lhs) };
+	let mut sum = Vec::with_capacity(long.len() + 1);
+	sum.extend_from_slice(long);
+	sum.push(0);
+	add_assign_words_at(&mut sum, short, 0);
+	sum
+}
+
+/// Adds `rhs * B^offset` to `acc` in place, propagating the carry. The sum must fit in `acc`.
+pub fn add_assign_words_at(acc: &mut [Word], rhs: &[Word], offset: usize) {
+	let mut carry = false;
+
+	for (i, w) in rhs.iter().enumerate() {
+		(acc[offset + i], carry) = acc[offset + i].carrying_add(*w, carry);
+	}
+
+	let mut i = offset + rhs.len();
+	while carry {
+		(acc[i], carry) = acc[i].overflowing_add(1);
+		i += 1;
+	}
+}
+
+/// Adds `rhs` to `acc` in place, returning whether or not the sum carried past the end of `acc`.
+pub fn add_assign_words(acc: &mut [Word], rhs: &[Word]) -> bool {
+	debug_assert!(acc.len() >= rhs.len());
+
+	let mut carry = false;
+
+	for (i, a) in acc.iter_mut().enumerate() {
+		if i >= rhs.len() && !carry {
+			break;
+		}
+		let w = if i < rhs.len() { rhs[i] } else { 0 };
+		(*a, carry) = a.carrying_add(w, carry);
+	}
+
+	carry
+}
+
+/// Subtracts `rhs * q` from `acc` in place, returning whether or not the result borrowed past the end of `acc`.
+pub fn mul_sub_assign_words(acc: &mut [Word], rhs: &[Word], q: Word) -> bool {
+	debug_assert!(acc.len() > rhs.len());
+
+	let mut product_carry = 0;
+	let mut borrow = false;
+
+	for (i, a) in acc.iter_mut().enumerate() {
+		let w = if i < rhs.len() { rhs[i] } else { 0 };
+		let (lo, hi) = w.carrying_mul(q, product_carry);
+		product_carry = hi;
+		(*a, borrow) = a.borrowing_sub(lo, borrow);
+	}
+
+	borrow || product_carry != 0
+}
+
+/// Shifts the words left by fewer than `WORD_BIT_COUNT` bits, with one extra word for the bits shifted out the top
+pub fn shl_bits(words: &[Word], shift: u32) -> Vec<Word> {
+	debug_assert!((shift as usize) < ubignumber::WORD_BIT_COUNT);
+
+	let mut shifted = Vec::with_capacity(words.len() + 1);
+	let mut shifted_out = 0;
+
+	for w in words {
+		shifted.push((w << shift) | shifted_out);
+		shifted_out = if shift == 0 { 0 } else { w >> (ubignumber::WORD_BIT_COUNT as u32 - shift) };
+	}
+
+	shifted.push(shifted_out);
+	shifted
+}
+
+/// Shifts the words right by fewer than `WORD_BIT_COUNT` bits
+pub fn shr_bits(words: &[Word], shift: u32) -> Vec<Word> {
+	debug_assert!((shift as usize) < ubignumber::WORD_BIT_COUNT);
+
+	let mut shifted = vec![0 ; words.len()];
+	let mut shifted_in = 0;
+
+	for i in (0..words.len()).rev() {
+		shifted[i] = (words[i] >> shift) | shifted_in;
+		shifted_in = if shift == 0 { 0 } else { words[i] << (ubignumber::WORD_BIT_COUNT as u32 - shift) };
+	}
+
+	shifted
+}
+
+/// Subtracts `rhs` from `acc` in place, returning whether or not the result borrowed past the end of `acc`.
+pub fn sub_assign_words(acc: &mut [Word], rhs: &[Word]) -> bool {
+	debug_assert!(acc.len() >= trimmed(rhs).len());
+
+	let mut borrow = false;
+
+	for i in 0..acc.len() {
+		if i >= rhs.len() && !borrow {
+			break;
+		}
+		let w = if i < rhs.len() { rhs[i] } else { 0 };
+		(acc[i], borrow) = acc[i].borrowing_sub(w, borrow);
+	}
+
+	borrow
+}
+
 /// Computes the quotient and remainder that is the result of dividing a two-word number by one word
 /// 
 /// Computes [hq|lq] = [hi|lo] / divisor, returns hq, lq, and the remainder, which will just be one word.
@@ -40,4 +875,4 @@ pub fn div_wide(hi: Word, lo: Word, divisor: Word) -> (Word, Word, Word) {
 	let (q, r) = (dividend / divisor, (dividend % divisor) as Word);
 	
 	((q >> ubignumber::WORD_BIT_COUNT) as Word, (q & ((1 << ubignumber::WORD_BIT_COUNT) - 1)) as Word, r)
-}
\ No newline at end of file
+}
@@ -1,8 +1,19 @@
 #![feature(bigint_helper_methods)]
 
+#[macro_use]
+mod macros;
+mod primitive;
+mod radix;
+mod bytes;
+mod float;
+mod pow;
+mod gcd;
+
 pub mod ubignumber;
 pub mod bignumber;
 pub mod int_utility;
+pub mod error;
+pub mod modular;
 
 #[cfg(test)]
 mod tests {
@@ -12,7 +23,7 @@ mod tests {
     use algebra_kit::algebra::{EuclideanDomain, Ring};
     use rand::{thread_rng, Rng};
 
-    use crate::ubignumber::UBigNumber;
+    use crate::{bignumber::BigNumber, error::ParseBigNumberErrorKind, int_utility, modular::{BarrettContext, MontgomeryContext}, ubignumber::{UBigNumber, Word}};
 
     #[test]
     fn test_conversions() {
@@ -26,21 +37,280 @@ mod tests {
         println!("{:?}", ubn);
     }
 
+    #[test]
+    fn test_decimal() {
+        let ubn = UBigNumber::from_dec_string("340282366920938463463374607431768211456");
+        assert_eq!(ubn.words, vec![0, 0, 1]);
+        assert_eq!(ubn.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(format!("{:>6}", UBigNumber::zero()), "     0");
+        assert_eq!(UBigNumber::from_dec_string("00012").to_string(), "12");
+
+        // big enough to go through the divide-and-conquer conversions, with runs of zeroes at the split points
+        for _ in 0..10 {
+            let len = thread_rng().gen_range(1..3000);
+            let mut digits: String = (0..len).map(|_| char::from(b'0' + thread_rng().gen_range(0..10))).collect();
+            digits.insert(0, '7');
+            digits.push_str(&"0".repeat(thread_rng().gen_range(0..100)));
+
+            let ubn = UBigNumber::from_dec_string(&digits);
+            assert_eq!(ubn.to_string(), digits);
+            assert_eq!(UBigNumber::from_dec_string(&ubn.to_dec_string()), ubn);
+        }
+
+        let ten = UBigNumber::from(10u64);
+        let mut power = UBigNumber::one();
+        for _ in 0..500 {
+            power *= &ten;
+        }
+        assert_eq!(power.to_string(), format!("1{}", "0".repeat(500)));
+        assert_eq!((&power - UBigNumber::one()).to_string(), "9".repeat(500));
+        assert_eq!((power * &ten + UBigNumber::one()).to_string(), format!("1{}1", "0".repeat(500)));
+    }
+
+    #[test]
+    fn test_radix() {
+        let ubn = UBigNumber::from_words(vec![0x0123456789abcdef, 0xfedcba9876543210, 0x7]);
+        assert_eq!(ubn.to_str_radix(16), "7fedcba98765432100123456789abcdef");
+        assert_eq!(UBigNumber::from_str_radix("7FEDCBA98765432100123456789ABCDEF", 16), Ok(ubn.clone()));
+        assert_eq!(UBigNumber::from_str_radix("zz", 36), Ok(UBigNumber::from(1295u64)));
+        assert_eq!(UBigNumber::zero().to_str_radix(8), "0");
+        assert!(UBigNumber::from_str_radix("", 10).is_err());
+        assert!(UBigNumber::from_str_radix("12", 2).is_err());
+        assert!(UBigNumber::from_str_radix("1é", 16).is_err());
+
+        assert_eq!(BigNumber::from_str_radix("-ff", 16).unwrap().to_str_radix(2), "-11111111");
+        assert_eq!(BigNumber::from_str_radix("+101", 2).unwrap(), BigNumber::from_primitive(5u8));
+        assert_eq!(BigNumber::from_str_radix("-0", 7).unwrap().to_str_radix(7), "0");
+        assert!(BigNumber::from_str_radix("-", 10).is_err());
+
+        // every radix should round trip, at sizes on both sides of the divide-and-conquer threshold
+        for radix in 2..=36 {
+            for size in [1, 5, 40, 100] {
+                let ubn = UBigNumber::rand(size);
+                let digits = ubn.to_str_radix(radix);
+                assert!(digits.len() == 1 || !digits.starts_with('0'));
+                assert_eq!(UBigNumber::from_str_radix(&digits, radix), Ok(ubn.clone()));
+
+                // check the digits themselves against plain repeated division
+                if size == 5 {
+                    let mut expected = vec![];
+                    let mut rest = ubn.clone();
+                    while !rest.is_zero() {
+                        let (q, r) = rest.div_rem_word(radix as Word);
+                        expected.push(char::from_digit(r as u32, radix).unwrap());
+                        rest = q;
+                    }
+                    assert_eq!(digits, expected.iter().rev().collect::<String>());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_formatting() {
+        let ubn = UBigNumber::from_words(vec![2, 1]);
+        assert_eq!(format!("{:x}", ubn), "10000000000000002");
+        assert_eq!(format!("{:#X}", UBigNumber::from(0xabcu64)), "0xABC");
+        assert_eq!(format!("{:#o}", UBigNumber::from(8u64)), "0o10");
+        assert_eq!(format!("{:b}", UBigNumber::zero()), "0");
+        assert_eq!(format!("{:#010b}", UBigNumber::from(5u64)), "0b00000101");
+        assert_eq!(format!("{:*^7}", UBigNumber::from(42u64)), "**42***");
+        assert_eq!(UBigNumber::from_hex_string(&format!("{:#x}", ubn)), ubn);
+
+        let bn = BigNumber::from_primitive(-255);
+        assert_eq!(format!("{}", bn), "-255");
+        assert_eq!(format!("{:#x}", bn), "-0xff");
+        assert_eq!(format!("{:08X}", bn), "-00000FF");
+        assert_eq!(format!("{:+}", -&bn), "+255");
+        assert_eq!(format!("{:>6}", bn), "  -255");
+        assert_eq!(format!("{:+o}", BigNumber::from_primitive(0)), "+0");
+
+        // should agree with the primitive formatting for anything that fits in one
+        for _ in 0..100 {
+            let x: i64 = (thread_rng().gen_range(0..i64::MAX) >> thread_rng().gen_range(0..63)).max(1);
+            let bn = BigNumber::from_primitive(-x);
+            assert_eq!(format!("{:+#020x}", bn), format!("{:+#020x}", x).replacen('+', "-", 1));
+            assert_eq!(format!("{:<25}|{:>+25}", bn, -&bn), format!("{:<25}|{:>+25}", -x, x));
+            assert_eq!(format!("{:#b}", bn.magnitude), format!("{:#b}", x));
+        }
+    }
+
+    #[test]
+    fn test_bytes() {
+        let ubn = UBigNumber::from_words(vec![0x0807060504030201, 0x0a09]);
+        assert_eq!(ubn.to_bytes_be(), vec![10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(ubn.to_bytes_le(), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(UBigNumber::from_bytes_be(&[0, 0, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]), ubn);
+        assert_eq!(UBigNumber::from_bytes_le(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0]), ubn);
+        assert_eq!(ubn.to_bytes_be_padded(12), Ok(vec![0, 0, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]));
+        assert!(ubn.to_bytes_le_padded(9).is_err());
+        assert_eq!(UBigNumber::zero().to_bytes_be(), vec![0]);
+        assert_eq!(UBigNumber::zero().to_bytes_le_padded(0), Ok(vec![]));
+        assert_eq!(UBigNumber::from_bytes_be(&[]), UBigNumber::zero());
+
+        assert_eq!(BigNumber::from_primitive(-128).to_signed_bytes_be(), vec![0x80]);
+        assert_eq!(BigNumber::from_primitive(128).to_signed_bytes_be(), vec![0, 0x80]);
+        assert_eq!(BigNumber::from_primitive(-129).to_signed_bytes_be(), vec![0xff, 0x7f]);
+        assert_eq!(BigNumber::from_primitive(-1).to_signed_bytes_be_padded(3), Ok(vec![0xff, 0xff, 0xff]));
+        assert!(BigNumber::from_primitive(-129).to_signed_bytes_le_padded(1).is_err());
+        assert_eq!(BigNumber::from_signed_bytes_be(&[]), BigNumber::from_primitive(0));
+
+        // check against the primitive two's complement bytes
+        for _ in 0..200 {
+            let x: i128 = thread_rng().gen::<i128>() >> thread_rng().gen_range(0..127);
+            let bn = BigNumber::from_primitive(x);
+            assert_eq!(bn.to_signed_bytes_be_padded(16), Ok(x.to_be_bytes().to_vec()));
+            assert_eq!(bn.to_signed_bytes_le_padded(16), Ok(x.to_le_bytes().to_vec()));
+            assert_eq!(BigNumber::from_signed_bytes_be(&x.to_be_bytes()), bn);
+            assert_eq!(BigNumber::from_signed_bytes_le(&bn.to_signed_bytes_le()), bn);
+            assert!(bn.to_signed_bytes_be().len() <= 16);
+
+            let u = x as u128;
+            assert_eq!(UBigNumber::from_primitive(u).to_bytes_be_padded(16), Ok(u.to_be_bytes().to_vec()));
+        }
+
+        for size in [1, 10, 100] {
+            let bn = BigNumber::from_sign_magnitude(thread_rng().gen(), UBigNumber::rand(size));
+            assert_eq!(BigNumber::from_signed_bytes_be(&bn.to_signed_bytes_be()), bn);
+        }
+    }
+
+    #[test]
+    fn test_primitive_conversions() {
+        assert_eq!(UBigNumber::from(0u8), UBigNumber::zero());
+        assert_eq!(UBigNumber::from(u128::MAX).words, vec![Word::MAX, Word::MAX]);
+        assert_eq!(BigNumber::from(i128::MIN).magnitude.words, vec![0, 1 << 63]);
+        assert!(BigNumber::from(-7i8).is_negative);
+
+        assert_eq!(u128::try_from(UBigNumber::from(u128::MAX)), Ok(u128::MAX));
+        assert!(u64::try_from(UBigNumber::from(u128::MAX)).is_err());
+        assert!(u128::try_from(UBigNumber::from(u128::MAX) + 1u8).is_err());
+        assert_eq!(i8::try_from(&UBigNumber::from(127u8)), Ok(127));
+        assert!(i8::try_from(&UBigNumber::from(128u8)).is_err());
+        assert_eq!(i128::try_from(BigNumber::from(i128::MIN)), Ok(i128::MIN));
+        assert!(i128::try_from(BigNumber::from(i128::MIN) - 1).is_err());
+        assert!(i128::try_from(BigNumber::from(u128::MAX)).is_err());
+        assert!(u32::try_from(BigNumber::from(-1)).is_err());
+        assert_eq!(u32::try_from(BigNumber::from(-1) + 1), Ok(0));
+        assert_eq!(isize::try_from(BigNumber::from(isize::MIN)), Ok(isize::MIN));
+
+        // round trips through every type should give back the same value
+        for _ in 0..100 {
+            let x: i128 = thread_rng().gen::<i128>() >> thread_rng().gen_range(0..127);
+            let bn = BigNumber::from(x);
+            assert_eq!(i128::try_from(&bn), Ok(x));
+            assert_eq!(i64::try_from(&bn).ok(), i64::try_from(x).ok());
+            assert_eq!(u16::try_from(&bn).ok(), u16::try_from(x).ok());
+            assert_eq!(u128::try_from(&bn).ok(), u128::try_from(x).ok());
+            assert_eq!(u64::try_from(&bn.magnitude).ok(), u64::try_from(x.unsigned_abs()).ok());
+        }
+    }
+
+    #[test]
+    fn test_float_conversions() {
+        let power = |n: usize| {
+            let mut words = vec![0; n / 64 + 1];
+            words[n / 64] = 1 << (n % 64);
+            UBigNumber::from_words(words)
+        };
+
+        // ties go to the even significand, anything past a tie goes up
+        assert_eq!((power(53) + 1u8).to_f64(), 2f64.powi(53));
+        assert_eq!((power(53) + 3u8).to_f64(), 2f64.powi(53) + 4.0);
+        assert_eq!((power(200) + power(147)).to_f64(), 2f64.powi(200));
+        assert_eq!((power(200) + power(147) + 1u8).to_f64(), 2f64.powi(200) + 2f64.powi(148));
+        assert_eq!((power(200) + power(148) + power(147)).to_f64(), 2f64.powi(200) + 2f64.powi(149));
+        assert_eq!((power(24) + 1u8).to_f32(), 2f32.powi(24));
+        assert_eq!((power(100) + power(76) + 1u8).to_f32(), 2f32.powi(100) + 2f32.powi(77));
+
+        // the biggest f64 and the overflow past it
+        let max = UBigNumber::try_from(f64::MAX).unwrap();
+        assert_eq!(max.to_f64(), f64::MAX);
+        assert_eq!((&max + power(969)).to_f64(), f64::MAX);
+        assert_eq!((&max + power(970)).to_f64(), f64::INFINITY);
+        assert_eq!(power(128).to_f32(), f32::INFINITY);
+        assert_eq!(BigNumber::from_sign_magnitude(true, power(1024)).to_f64(), f64::NEG_INFINITY);
+        assert_eq!(UBigNumber::zero().to_f64(), 0.0);
+
+        assert_eq!(UBigNumber::try_from(2f64.powi(64)), Ok(power(64)));
+        assert_eq!(UBigNumber::try_from(-0.5), Ok(UBigNumber::zero()));
+        assert_eq!(BigNumber::try_from(-0.99), Ok(BigNumber::from(0)));
+        assert_eq!(BigNumber::try_from(-1234.99), Ok(BigNumber::from(-1234)));
+        assert_eq!(BigNumber::try_from(f64::from_bits(1)), Ok(BigNumber::from(0)));
+        assert!(UBigNumber::try_from(-1.0).is_err());
+        assert!(BigNumber::try_from(f64::NAN).is_err());
+        assert!(BigNumber::try_from(f64::NEG_INFINITY).is_err());
+
+        // should agree with the primitive conversions, which are also correctly rounded
+        for _ in 0..1000 {
+            let x: i128 = thread_rng().gen::<i128>() >> thread_rng().gen_range(0..127);
+            assert_eq!(BigNumber::from(x).to_f64(), x as f64);
+            assert_eq!(BigNumber::from(x).to_f32(), x as f32);
+            assert_eq!(BigNumber::try_from(x as f64).unwrap().to_f64(), x as f64);
+
+            let f = f64::from_bits(thread_rng().gen_range(0..0x7ff0000000000000)) * if x < 0 { -1.0 } else { 1.0 };
+            let truncated = BigNumber::try_from(f).unwrap();
+            assert_eq!(truncated.to_f64(), f.trunc());
+            if f.abs() < 1e38 {
+                assert_eq!(i128::try_from(truncated), Ok(f as i128));
+            }
+        }
+    }
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!("  1_000_000\n".parse::<UBigNumber>(), Ok(UBigNumber::from(1000000u64)));
+        assert_eq!("0xDEAD_beef".parse::<UBigNumber>(), Ok(UBigNumber::from(0xdeadbeefu64)));
+        assert_eq!("0o777".parse::<UBigNumber>(), Ok(UBigNumber::from(0o777u64)));
+        assert_eq!("0b1010".parse::<UBigNumber>(), Ok(UBigNumber::from(10u64)));
+        assert_eq!("007".parse::<UBigNumber>(), Ok(UBigNumber::from(7u64)));
+        assert_eq!("-0x10".parse::<BigNumber>(), Ok(BigNumber::from_primitive(-16)));
+        assert_eq!(" +42 ".parse::<BigNumber>(), Ok(BigNumber::from_primitive(42)));
+
+        let kind = |s: &str| *s.parse::<BigNumber>().unwrap_err().kind();
+        assert_eq!(kind(""), ParseBigNumberErrorKind::Empty);
+        assert_eq!(kind("  -0x_ "), ParseBigNumberErrorKind::Empty);
+        assert_eq!(kind("0z12"), ParseBigNumberErrorKind::InvalidRadixPrefix);
+        assert_eq!(kind(" 12a4"), ParseBigNumberErrorKind::InvalidDigit { position: 3, digit: 'a' });
+        assert_eq!(kind("-0b102"), ParseBigNumberErrorKind::InvalidDigit { position: 5, digit: '2' });
+        assert_eq!(kind("1é"), ParseBigNumberErrorKind::InvalidDigit { position: 1, digit: 'é' });
+        assert!("-5".parse::<UBigNumber>().is_err());
+
+        // the old hex constructor still panics on bad input, but only with a proper message
+        assert!(std::panic::catch_unwind(|| UBigNumber::from_hex_string("0xéé")).is_err());
+        assert_eq!(UBigNumber::from("0x"), UBigNumber::zero());
+    }
+
     // MARK: Arithmetic Tests
 
     #[test]
     fn test_add() {
         let a: UBigNumber = "0x000000010000000200000003000000040000000500000006".into();
         let b: UBigNumber = "0x000000010000000100000001000000010000000100000001".into();
-        assert_eq!(a + b, "0x000000020000000300000004000000050000000600000007".into());
+        assert_eq!(a + b, UBigNumber::from("0x000000020000000300000004000000050000000600000007"));
 
         let c: UBigNumber = "0xFFFFFFFFFFFFFFFF".into();
-        assert_eq!(c + 1.into(), "0x10000000000000000".into());
+        assert_eq!(&c + 1u64, UBigNumber::from("0x10000000000000000"));
 
-        // I wonder what happens here
-        println!("{:?}", UBigNumber::from_int(1) - 2.into());
+        // Going negative is an error, like it is for primitives
+        let (one, two) = (UBigNumber::from_int(1), UBigNumber::from_int(2));
+        assert_eq!(one.checked_sub(&two), None);
+        assert_eq!(two.checked_sub(&one), Some(UBigNumber::one()));
+        assert_eq!(one.saturating_sub(&two), UBigNumber::zero());
+        assert_eq!(one.overflowing_sub(&two), (UBigNumber::from(Word::MAX), true));
+        assert_eq!(c.overflowing_sub(&one), (UBigNumber::from(Word::MAX - 1), false));
+        assert_eq!(one.abs_diff(&c), UBigNumber::from(Word::MAX - 1));
+        assert_eq!(c.abs_diff(&one), UBigNumber::from(Word::MAX - 1));
 
+        assert_eq!(UBigNumber::try_from(BigNumber::from(5)), Ok(UBigNumber::from(5u8)));
+        assert!(UBigNumber::try_from(&BigNumber::from(-5)).is_err());
+    }
 
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "attempt to subtract with overflow")]
+    fn test_sub_underflow() {
+        let _ = UBigNumber::from_int(1) - UBigNumber::from_int(2);
     }
 
     #[test]
@@ -56,10 +326,488 @@ mod tests {
         assert_eq!(
             UBigNumber::from_hex_string("0x558CE7C54D02B1FC4F41C55BD511D549D8A6C8F64F06BCAAB23FF1DE295198E9") *
             UBigNumber::from_hex_string("0x9779F079B986C0AB28067950DB40BB87AA1FCA6C89DA76AA689A47918E060C78"),
-            "0x329edcabb3905d57a66426b26955d284a9020c8907621ae56070df059c7658ae6900b41a962383d7facf2bb89c11c94deffcfa00ddafa441203a3fc704e09938".into()
+            UBigNumber::from("0x329edcabb3905d57a66426b26955d284a9020c8907621ae56070df059c7658ae6900b41a962383d7facf2bb89c11c94deffcfa00ddafa441203a3fc704e09938")
         )
     }
 
+    #[test]
+    fn test_karatsuba() {
+        let mut rng = thread_rng();
+
+        // balanced, just around the threshold, and very unbalanced operands
+        for (lhs_len, rhs_len) in [(80, 80), (81, 80), (200, 150), (301, 299), (1000, 90), (90, 700)] {
+            let lhs = UBigNumber::rand(lhs_len);
+            let rhs = UBigNumber::rand(rhs_len);
+            assert_eq!(int_utility::mul(&lhs.words, &rhs.words), int_utility::word_mul(lhs.words.clone(), rhs.words.clone()));
+        }
+
+        for _ in 0..10 {
+            let lhs = UBigNumber::rand(rng.gen_range(80..400));
+            let rhs = UBigNumber::rand(rng.gen_range(80..400));
+            let product = &lhs * &rhs;
+            assert_eq!(product, UBigNumber::from_words(int_utility::word_mul(lhs.words, rhs.words)));
+        }
+    }
+
+    #[test]
+    fn test_toom_cook() {
+        let mut rng = thread_rng();
+
+        // Toom-Cook works on any sizes, not just big ones, so small ones are a good check of the interpolation
+        for _ in 0..20 {
+            let lhs = UBigNumber::rand(rng.gen_range(4..300));
+            let rhs = UBigNumber::rand(rng.gen_range((lhs.len() / 2 + 1)..(2 * lhs.len())));
+            let expected = int_utility::word_mul(lhs.words.clone(), rhs.words.clone());
+            assert_eq!(int_utility::toom_3_mul(&lhs.words, &rhs.words), expected);
+            assert_eq!(int_utility::toom_4_mul(&lhs.words, &rhs.words), expected);
+        }
+
+        // big enough to go through every tier
+        let lhs = UBigNumber::rand(int_utility::TOOM_4_THRESHOLD + 100);
+        let rhs = UBigNumber::rand(int_utility::TOOM_4_THRESHOLD);
+        assert_eq!(int_utility::mul(&lhs.words, &rhs.words), int_utility::word_mul(lhs.words, rhs.words));
+    }
+
+    #[test]
+    fn test_ntt() {
+        let mut rng = thread_rng();
+
+        for _ in 0..20 {
+            let lhs = UBigNumber::rand(rng.gen_range(1..500));
+            let rhs = UBigNumber::rand(rng.gen_range(1..500));
+            assert_eq!(int_utility::ntt_mul(&lhs.words, &rhs.words), int_utility::word_mul(lhs.words, rhs.words));
+        }
+
+        // every word as big as it gets, so the convolution coefficients are as big as they get
+        let all_ones = vec![Word::MAX ; 1000];
+        assert_eq!(int_utility::ntt_mul(&all_ones, &all_ones), int_utility::word_mul(all_ones.clone(), all_ones.clone()));
+
+        let lhs = UBigNumber::rand(3000);
+        let rhs = UBigNumber::rand(2500);
+        assert_eq!(int_utility::ntt_mul(&lhs.words, &rhs.words), int_utility::mul(&lhs.words, &rhs.words));
+    }
+
+    #[test]
+    fn test_square() {
+        assert_eq!(UBigNumber::zero().square(), UBigNumber::zero());
+        assert_eq!(UBigNumber::from_int(Word::MAX).square(), UBigNumber::from("0xFFFFFFFFFFFFFFFE0000000000000001"));
+
+        // one size in each tier, besides NTT which is too slow to compare against in debug builds
+        for len in [1, 2, 17, int_utility::KARATSUBA_THRESHOLD + 3, int_utility::TOOM_3_THRESHOLD + 5, int_utility::TOOM_4_THRESHOLD + 1] {
+            let mut n = UBigNumber::rand(len);
+            let expected = &n * &n;
+            assert_eq!(n.square(), expected);
+            n.square_assign();
+            assert_eq!(n, expected);
+        }
+
+        for _ in 0..10 {
+            let n = UBigNumber::rand(thread_rng().gen_range(1..400));
+            assert_eq!(int_utility::ntt_square(&n.words), int_utility::word_mul(n.words.clone(), n.words.clone()));
+            assert_eq!(int_utility::toom_3_square(&n.words), int_utility::word_mul(n.words.clone(), n.words.clone()));
+            assert_eq!(int_utility::toom_4_square(&n.words), int_utility::word_mul(n.words.clone(), n.words.clone()));
+        }
+    }
+
+    #[test]
+    fn test_bitwise() {
+        let a = UBigNumber::from_words(vec![0xff00ff00ff00ff00, 0xf0f0, 1]);
+        let b = UBigNumber::from_words(vec![0x0ff00ff00ff00ff0, 0xf0f0]);
+        assert_eq!(&a & &b, UBigNumber::from_words(vec![0x0f000f000f000f00, 0xf0f0]));
+        assert_eq!(&a ^ &b, UBigNumber::from_words(vec![0xf0f0f0f0f0f0f0f0, 0, 1]));
+        assert_eq!(&a | &b, UBigNumber::from_words(vec![0xfff0fff0fff0fff0, 0xf0f0, 1]));
+        assert_eq!(&b ^ &b, UBigNumber::zero());
+        assert_eq!((&a & UBigNumber::from(0xffu8)).words, vec![0]);
+
+        let mut c = a.clone();
+        c &= &b;
+        c ^= &a;
+        assert_eq!(c, &a - (&a & &b));
+
+        assert_eq!(a.bit_length(), 129);
+        assert_eq!(UBigNumber::zero().bit_length(), 0);
+        assert!(a.bit(128) && a.bit(8) && !a.bit(0) && !a.bit(1000));
+        assert_eq!(a.count_ones(), 32 + 8 + 1);
+        assert_eq!(a.trailing_zeros(), Some(8));
+        assert_eq!(UBigNumber::from_words(vec![0, 0, 4]).trailing_zeros(), Some(130));
+        assert_eq!(UBigNumber::zero().trailing_zeros(), None);
+        assert_eq!(a.leading_zeros(), 63);
+
+        let mut d = UBigNumber::zero();
+        d.set_bit(200, true);
+        assert_eq!(d.words, vec![0, 0, 0, 1 << 8]);
+        assert!(d.is_power_of_two());
+        assert_eq!(d.next_power_of_two(), d);
+        d.set_bit(3, true);
+        assert!(!d.is_power_of_two());
+        assert_eq!(d.next_power_of_two().bit_length(), 202);
+        d.set_bit(200, false);
+        assert_eq!(d.words, vec![8]);
+        d.set_bit(500, false);
+        assert_eq!(d.words, vec![8]);
+        assert_eq!(UBigNumber::zero().next_power_of_two(), UBigNumber::one());
+        assert!(!UBigNumber::zero().is_power_of_two());
+
+        // these should agree with the primitive versions
+        for _ in 0..100 {
+            let (x, y): (u128, u128) = (thread_rng().gen(), thread_rng().gen::<u128>() >> thread_rng().gen_range(0..128));
+            let (bx, by) = (UBigNumber::from(x), UBigNumber::from(y));
+            assert_eq!(&bx & &by, x & y);
+            assert_eq!(&bx ^ &by, x ^ y);
+            assert_eq!(by.bit_length(), 128 - y.leading_zeros() as u64);
+            assert_eq!(by.count_ones(), y.count_ones() as u64);
+            assert_eq!(by.next_power_of_two(), y.checked_next_power_of_two().map_or(UBigNumber::from(u128::MAX) + 1u8, UBigNumber::from));
+        }
+    }
+
+    #[test]
+    fn test_shifts() {
+        let a = UBigNumber::from_words(vec![0x8000000000000001, 3]);
+        assert_eq!((&a << 64u32).words, vec![0, 0x8000000000000001, 3]);
+        assert_eq!((&a << 65usize).words, vec![0, 2, 7]);
+        assert_eq!((&a << 0u8), a);
+        assert_eq!((&a << 200i64).words, vec![0, 0, 0, 0x100, 0x380]);
+        assert_eq!((&a >> 64u64).words, vec![3]);
+        assert_eq!((&a >> 1i32).words, vec![0xc000000000000000, 1]);
+        assert_eq!((&a >> 66u16).words, vec![0]);
+        assert_eq!((&a >> 1000u128), UBigNumber::zero());
+        assert_eq!((&a >> u128::MAX), UBigNumber::zero());
+        assert_eq!(UBigNumber::zero() << 1000, UBigNumber::zero());
+
+        let mut b = a.clone();
+        b <<= 130;
+        b >>= 130u8;
+        assert_eq!(b, a);
+        b.shl_words(3);
+        assert_eq!(b, &a << 192);
+        b.shr_words(4);
+        assert_eq!(b.words, vec![3]);
+        b.shr_words(1);
+        assert_eq!(b.words, vec![0]);
+
+        // shifting should be the same as multiplying or dividing by a power of two, with normalized results
+        for _ in 0..50 {
+            let x = UBigNumber::rand(thread_rng().gen_range(1..10));
+            let n: usize = thread_rng().gen_range(0..1000);
+            let mut power = UBigNumber::zero();
+            power.set_bit(n as u64, true);
+            assert_eq!(&x << n, &x * &power);
+            let shifted = &x >> n;
+            assert_eq!(shifted, &x / &power);
+            assert!(shifted.is_zero() || shifted.words.last() != Some(&0));
+            assert_eq!(BigNumber::from(x.clone()) << n as i16, BigNumber::from(&x * &power));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to shift left by a negative amount")]
+    fn test_negative_shift() {
+        let _ = BigNumber::from(1) << -1;
+    }
+
+    #[test]
+    fn test_signed_bitwise() {
+        assert_eq!(!BigNumber::from(0), BigNumber::from(-1));
+        assert_eq!(!&BigNumber::from(-5), BigNumber::from(4));
+        assert_eq!(BigNumber::from(-12) & BigNumber::from(10), BigNumber::from(-12 & 10));
+        assert_eq!(BigNumber::from(-12) | BigNumber::from(10), BigNumber::from(-12 | 10));
+        assert_eq!(BigNumber::from(-12) ^ BigNumber::from(-10), BigNumber::from(-12 ^ -10));
+        assert_eq!(BigNumber::from(-1) >> 100, BigNumber::from(-1));
+        assert_eq!(BigNumber::from(-7) >> 1, BigNumber::from(-4));
+        assert_eq!(BigNumber::from(7) >> 1, BigNumber::from(3));
+        assert_eq!(BigNumber::from(-3) << 130, BigNumber::from(-3) * BigNumber::from(u128::MAX / 2 + 1) * 8);
+
+        // a word of all ones is the sign extension of a negative number, so these need the extra word
+        let min = BigNumber::from(i128::MIN);
+        assert_eq!(&min & BigNumber::from(-1), min);
+        assert_eq!(&min ^ BigNumber::from(u128::MAX), &min - 1);
+
+        // these should agree with the primitive versions
+        for _ in 0..200 {
+            let random = || thread_rng().gen::<i128>() >> thread_rng().gen_range(0..127);
+            let (x, y) = (random(), random());
+            let (bx, by) = (BigNumber::from(x), BigNumber::from(y));
+            assert_eq!(&bx & &by, BigNumber::from(x & y));
+            assert_eq!(&bx | &by, BigNumber::from(x | y));
+            assert_eq!(&bx ^ &by, BigNumber::from(x ^ y));
+            assert_eq!(!&bx, BigNumber::from(!x));
+
+            let n = thread_rng().gen_range(0..200);
+            assert_eq!(&bx >> n, BigNumber::from(x >> n.min(127)));
+            assert_eq!(&bx << n >> n, bx);
+        }
+
+        // and these identities should hold for anything
+        for size in [1, 3, 20] {
+            let a = BigNumber::from_sign_magnitude(thread_rng().gen(), UBigNumber::rand(size));
+            let b = BigNumber::from_sign_magnitude(thread_rng().gen(), UBigNumber::rand(size + 1));
+            assert_eq!(&a ^ &b, (&a | &b) - (&a & &b));
+            assert_eq!((&a & &b) + (&a | &b), &a + &b);
+            assert_eq!(!(&a & &b), !&a | !&b);
+            assert_eq!(!!a.clone(), a);
+
+            let n = thread_rng().gen_range(0..500);
+            let power = BigNumber::from(1) << n;
+            assert_eq!(&a << n, &a * &power);
+            assert_eq!(&a >> n, (&a - a.euc_rem(power.clone())) / &power);
+        }
+    }
+
+    #[test]
+    fn test_pow() {
+        let three = UBigNumber::from(3u8);
+        assert_eq!(three.pow(0), UBigNumber::one());
+        assert_eq!(three.pow(1), three);
+        assert_eq!(three.pow(40), 3u128.pow(40));
+        assert_eq!(UBigNumber::zero().pow(0), UBigNumber::one());
+        assert_eq!(UBigNumber::zero().pow_ubn(&UBigNumber::rand(10)), UBigNumber::zero());
+        assert_eq!(UBigNumber::one().pow_ubn(&UBigNumber::rand(10)), UBigNumber::one());
+        assert_eq!(UBigNumber::from(2u8).pow(1000), UBigNumber::one() << 1000);
+        assert_eq!(UBigNumber::from(10u8).pow(300).to_string(), format!("1{}", "0".repeat(300)));
+
+        assert_eq!(BigNumber::from(-2).pow(3), BigNumber::from(-8));
+        assert_eq!(BigNumber::from(-2).pow_ubn(&UBigNumber::from(64u8)), BigNumber::from(1u128 << 64));
+        assert_eq!(BigNumber::from(-7).power(5), BigNumber::from(-16807));
+        assert_eq!(BigNumber::from(-7).power(0), BigNumber::one());
+
+        assert_eq!(three.checked_pow(40, 64), Some(three.pow(40)));
+        assert_eq!(three.checked_pow(41, 64), None);
+        assert_eq!(three.checked_pow(u64::MAX, 1 << 20), None);
+        assert_eq!(UBigNumber::one().checked_pow(u64::MAX, 1), Some(UBigNumber::one()));
+        assert_eq!(BigNumber::from(-3).checked_pow(3, 5), Some(BigNumber::from(-27)));
+        assert_eq!(BigNumber::from(-3).checked_pow(3, 4), None);
+
+        // every window size should agree with repeated multiplication
+        for _ in 0..10 {
+            let base = UBigNumber::rand(thread_rng().gen_range(1..4));
+            let exponent: u64 = thread_rng().gen_range(0..600);
+            let mut expected = UBigNumber::one();
+            for _ in 0..exponent {
+                expected *= &base;
+            }
+            assert_eq!(base.pow(exponent), expected);
+        }
+    }
+
+    #[test]
+    fn test_modpow() {
+        let m = UBigNumber::from(1000000007u64);
+        assert_eq!(UBigNumber::from(2u8).modpow(&UBigNumber::from(10u8), &m), 1024u16);
+        assert_eq!(UBigNumber::from(2u8).modpow(&(&m - 1u8), &m), UBigNumber::one());
+        assert_eq!(UBigNumber::from(5u8).modpow(&UBigNumber::zero(), &m), UBigNumber::one());
+        assert_eq!(UBigNumber::from(5u8).modpow(&UBigNumber::zero(), &UBigNumber::one()), UBigNumber::zero());
+        assert_eq!(UBigNumber::from(3u8).modpow(&UBigNumber::from(5u8), &UBigNumber::from(16u8)), 243 % 16u16);
+
+        // Fermat's little theorem for the Mersenne prime 2^127 - 1
+        let p = (UBigNumber::one() << 127) - 1u8;
+        let a = UBigNumber::rand(4);
+        assert_eq!(a.modpow(&p, &p), &a % &p);
+
+        // should agree with pow followed by one remainder, for odd and even moduli of a few sizes
+        for size in [1, 2, 5, 12] {
+            let base = UBigNumber::rand(size + 1);
+            let exponent = UBigNumber::from(thread_rng().gen_range(1..300u64));
+            let mut modulus = UBigNumber::rand(size);
+            for odd in [true, false] {
+                modulus.set_bit(0, odd);
+                let expected = base.pow_ubn(&exponent) % &modulus;
+                assert_eq!(base.modpow(&exponent, &modulus), expected);
+            }
+        }
+
+        // negative exponents need an inverse
+        let m = BigNumber::from(101);
+        assert_eq!(BigNumber::from(3).modpow(&BigNumber::from(-1), &m), Some(BigNumber::from(34)));
+        assert_eq!(BigNumber::from(-3).modpow(&BigNumber::from(3), &m), Some(BigNumber::from(74)));
+        assert_eq!(BigNumber::from(-3).modpow(&BigNumber::from(-2), &-&m), Some(BigNumber::from(45)));
+        assert_eq!(BigNumber::from(6).modpow(&BigNumber::from(-1), &BigNumber::from(9)), None);
+        assert_eq!(BigNumber::from(6).modpow(&BigNumber::from(2), &BigNumber::from(9)), Some(BigNumber::from(0)));
+    }
+
+    #[test]
+    fn test_montgomery() {
+        let modulus = UBigNumber::rand(4) | UBigNumber::one();
+        let context = MontgomeryContext::new(&modulus);
+        assert_eq!(context.modulus(), modulus);
+        assert_eq!(context.word_len(), 4);
+
+        let (a, b) = (UBigNumber::rand(4) % &modulus, UBigNumber::rand(5));
+        let (a_mont, b_mont) = (context.to_mont(&a), context.to_mont(&b));
+        assert_eq!(context.from_mont(&a_mont), a);
+        assert_eq!(context.from_mont(&b_mont), &b % &modulus);
+
+        let mut out = vec![0; 4];
+        context.mul(&mut out, &a_mont, &b_mont);
+        assert_eq!(context.from_mont(&out), &a * &b % &modulus);
+        context.square(&mut out, &a_mont, &mut [0; 8]);
+        assert_eq!(context.from_mont(&out), a.square() % &modulus);
+
+        let exponent = UBigNumber::rand(2);
+        context.pow(&mut out, &a_mont, &exponent);
+        assert_eq!(context.from_mont(&out), a.modpow(&exponent, &modulus));
+        context.pow(&mut out, &a_mont, &UBigNumber::zero());
+        assert_eq!(context.from_mont(&out), UBigNumber::one());
+
+        // a modulus right under a power of B makes the accumulator overflow the most
+        let modulus = UBigNumber::from_words(vec![Word::MAX; 3]);
+        let context = MontgomeryContext::new(&modulus);
+        let x = &modulus - 1u8;
+        let x_mont = context.to_mont(&x);
+        context.mul(&mut out[..3], &x_mont, &x_mont);
+        assert_eq!(context.from_mont(&out[..3]), UBigNumber::one());
+        context.square(&mut out[..3], &x_mont, &mut [0; 6]);
+        assert_eq!(context.from_mont(&out[..3]), UBigNumber::one());
+
+        // squaring gives exactly the same words as multiplying a number by itself
+        for (modulus, x) in [(modulus, x), (UBigNumber::rand(7) | UBigNumber::one(), UBigNumber::rand(7))] {
+            let n = modulus.len();
+            let context = MontgomeryContext::new(&modulus);
+            let neg_inv = int_utility::word_inverse(modulus[0]).wrapping_neg();
+            let x_mont = context.to_mont(&x);
+            let (mut squared, mut multiplied, mut product) = (vec![0; n], vec![0; n], vec![0; 2 * n]);
+            int_utility::montgomery_square(&mut squared, &x_mont, &modulus.words, neg_inv, &mut product);
+            int_utility::montgomery_mul(&mut multiplied, &x_mont, &x_mont, &modulus.words, neg_inv);
+            assert_eq!(squared, multiplied);
+        }
+
+        // a chain of operations sharing one context
+        let modulus = UBigNumber::rand(10) | UBigNumber::one();
+        let context = MontgomeryContext::new(&modulus);
+        let (mut acc, mut scratch) = (context.to_mont(&UBigNumber::one()), vec![0; 10]);
+        let mut expected = UBigNumber::one();
+        for _ in 0..100 {
+            let x = UBigNumber::rand(10);
+            context.mul(&mut scratch, &acc, &context.to_mont(&x));
+            std::mem::swap(&mut acc, &mut scratch);
+            expected = expected * &x % &modulus;
+        }
+        assert_eq!(context.from_mont(&acc), expected);
+    }
+
+    #[test]
+    fn test_barrett() {
+        for size in [1, 2, 7, 30] {
+            for _ in 0..20 {
+                let modulus = UBigNumber::rand(size) >> thread_rng().gen_range(0..64u32);
+                if modulus.is_zero() {
+                    continue;
+                }
+                let context = BarrettContext::new(&modulus);
+                assert_eq!(context.modulus(), &modulus);
+
+                let (a, b) = (UBigNumber::rand(size), UBigNumber::rand(size + 1));
+                assert_eq!(context.reduce(&a), &a % &modulus);
+                assert_eq!(context.reduce(&(&a * &a)), &a * &a % &modulus);
+                assert_eq!(context.mul_mod(&a, &b), &a * &b % &modulus);
+
+                // the biggest input that doesn't fall back to dividing, and one past it
+                let mut biggest = UBigNumber::one();
+                biggest.shl_words(2 * modulus.len());
+                biggest -= 1u8;
+                assert_eq!(context.reduce(&biggest), &biggest % &modulus);
+                assert_eq!(context.reduce(&(&biggest + 1u8)), (&biggest + 1u8) % &modulus);
+                assert_eq!(context.reduce(&modulus), UBigNumber::zero());
+            }
+        }
+
+        let context = BarrettContext::new(&UBigNumber::one());
+        assert_eq!(context.reduce(&UBigNumber::rand(3)), UBigNumber::zero());
+        assert_eq!(context.pow_mod(&UBigNumber::from(5u8), &UBigNumber::zero()), UBigNumber::zero());
+
+        let modulus = UBigNumber::one() << 300;
+        let context = BarrettContext::new(&modulus);
+        let (base, exponent) = (UBigNumber::rand(6), UBigNumber::from(1000u16));
+        assert_eq!(context.pow_mod(&base, &exponent), base.pow(1000) % &modulus);
+    }
+
+    #[test]
+    fn test_gcd() {
+        let (a, b) = (UBigNumber::from(48u8), UBigNumber::from(180u8));
+        assert_eq!(a.gcd(&b), 12u8);
+        assert_eq!(a.lcm(&b), 720u16);
+        assert_eq!(a.gcd(&UBigNumber::zero()), a);
+        assert_eq!(UBigNumber::zero().gcd(&UBigNumber::zero()), UBigNumber::zero());
+        assert_eq!(UBigNumber::zero().lcm(&b), UBigNumber::zero());
+        assert_eq!(BigNumber::from(-48).gcd(&BigNumber::from(180)), BigNumber::from(12));
+        assert_eq!(BigNumber::from(-48).lcm(&BigNumber::from(-180)), BigNumber::from(720));
+
+        let (g, x, y) = BigNumber::from(-48).extended_gcd(&BigNumber::from(180));
+        assert_eq!(g, BigNumber::from(12));
+        assert_eq!(BigNumber::from(-48) * &x + BigNumber::from(180) * &y, g);
+
+        // consecutive Fibonacci numbers are the worst case for the Euclidean algorithm
+        let (mut f0, mut f1) = (UBigNumber::zero(), UBigNumber::one());
+        for _ in 0..2000 {
+            (f0, f1) = (f1.clone(), f0 + &f1);
+        }
+        assert_eq!(f0.gcd(&f1), UBigNumber::one());
+        let (g, x, y) = f1.extended_gcd(&f0);
+        assert_eq!(g, UBigNumber::one());
+        assert_eq!(BigNumber::from(f1.clone()) * &x + BigNumber::from(f0.clone()) * &y, BigNumber::one());
+
+        // random numbers with a known common factor, from a single word (where Lehmer steps stop) up to many
+        for (size, factor_size) in [(1, 1), (2, 1), (3, 1), (10, 4), (40, 13), (100, 1)] {
+            let factor = UBigNumber::rand(factor_size) | UBigNumber::one();
+            let a = UBigNumber::rand(size) * &factor;
+            let b = (UBigNumber::rand(size + 1) * &factor) << thread_rng().gen_range(0..100u32);
+            let g = a.gcd(&b);
+            assert!((&g % &factor).is_zero());
+            assert!((&a % &g).is_zero() && (&b % &g).is_zero());
+            assert_eq!((&a / &g).gcd(&(&b / &g)), UBigNumber::one());
+            assert_eq!(b.gcd(&a), g);
+            assert_eq!(a.lcm(&b), &a * &b / &g);
+
+            let (eg, x, y) = a.extended_gcd(&b);
+            assert_eq!(eg, g);
+            assert_eq!(BigNumber::from(a.clone()) * &x + BigNumber::from(b.clone()) * &y, BigNumber::from(g.clone()));
+            let (_, x, y) = b.extended_gcd(&a);
+            assert_eq!(BigNumber::from(b.clone()) * &x + BigNumber::from(a.clone()) * &y, BigNumber::from(g));
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        let m = UBigNumber::from(101u8);
+        assert_eq!(UBigNumber::from(3u8).mod_inverse(&m), Some(UBigNumber::from(34u8)));
+        assert_eq!(UBigNumber::from(104u8).mod_inverse(&m), Some(UBigNumber::from(34u8)));
+        assert_eq!(UBigNumber::zero().mod_inverse(&m), None);
+        assert_eq!(UBigNumber::from(6u8).mod_inverse(&UBigNumber::from(9u8)), None);
+        assert_eq!(UBigNumber::from(6u8).mod_inverse(&UBigNumber::one()), Some(UBigNumber::zero()));
+        assert_eq!(BigNumber::from(-3).mod_inverse(&BigNumber::from(101)), Some(UBigNumber::from(67u8)));
+        assert_eq!(BigNumber::from(3).mod_inverse(&BigNumber::from(-101)), Some(UBigNumber::from(34u8)));
+
+        // powers of two, including ones smaller than a word
+        assert_eq!(UBigNumber::from(3u8).mod_inverse(&UBigNumber::from(16u8)), Some(UBigNumber::from(11u8)));
+        assert_eq!(UBigNumber::from(4u8).mod_inverse(&UBigNumber::from(16u8)), None);
+        assert_eq!(UBigNumber::from(7u8).mod_inverse(&UBigNumber::from(2u8)), Some(UBigNumber::one()));
+        for bits in [64u32, 100, 128, 1000, 4097] {
+            let modulus = UBigNumber::one() << bits;
+            let a = UBigNumber::rand(70) | UBigNumber::one();
+            let inverse = a.mod_inverse(&modulus).unwrap();
+            assert!(inverse < modulus);
+            assert_eq!(&a * &inverse % &modulus, UBigNumber::one());
+        }
+
+        for size in [1, 3, 20] {
+            let modulus = UBigNumber::rand(size) | UBigNumber::from(3u8);
+            let a = UBigNumber::rand(size + 2);
+            match a.mod_inverse(&modulus) {
+                Some(inverse) => {
+                    assert!(inverse < modulus);
+                    assert_eq!(&a * &inverse % &modulus, UBigNumber::one());
+                },
+                None => assert_ne!(a.gcd(&modulus), UBigNumber::one()),
+            }
+        }
+
+        // the inverse of Montgomery's R, since R * R^-1 = 1 is what makes Montgomery form work
+        let modulus = UBigNumber::rand(4) | UBigNumber::one();
+        let r = UBigNumber::one() << 256u32;
+        let r_inverse = r.mod_inverse(&modulus).unwrap();
+        assert_eq!(&r * &r_inverse % &modulus, UBigNumber::one());
+        assert_eq!(BigNumber::from(r).modpow(&BigNumber::from(-1), &BigNumber::from(modulus)), Some(BigNumber::from(r_inverse)));
+    }
+
     #[test]
     fn test_clone() {
         let a = UBigNumber::rand(4);
@@ -77,7 +825,7 @@ mod tests {
             let divisor: UBigNumber = "0xD4024CBD9C6BAE10".into();
             let correct_quotient: UBigNumber = "f8b36482cdc11eae615a3c1c9c8ad986".into();
             let correct_remainder: UBigNumber = "43b4eec6252d59c9".into();
-            assert_eq!(correct_quotient, dividend.clone() / divisor.clone());
+            assert_eq!(correct_quotient, &dividend / &divisor);
             assert_eq!(correct_remainder, dividend % divisor);
         }
 
@@ -97,31 +845,31 @@ mod tests {
         // so we should get two.
         {
             let known_rand: UBigNumber = "0x56C1ADE683B78C807948E66BDA765CC9BA2FB6F85667311E".into();
-            let twice = known_rand.clone() * 2.into();
+            let twice = &known_rand * 2u64;
             let (q, r) = twice.quotient_and_remainder(&known_rand);
-            assert_eq!(q, 2.into());
+            assert_eq!(q, 2u64);
             assert_eq!(r, UBigNumber::zero());
         }
 
-        assert_eq!(UBigNumber::from_int(1) / 1.into(), 1.into());
+        assert_eq!(UBigNumber::from_int(1) / UBigNumber::one(), UBigNumber::one());
         for _ in 0..10 {
             let rand = UBigNumber::rand(3); // arbitrary size
-            assert_eq!(rand.clone() / 1.into(), rand);
-            assert_eq!(rand.clone() / rand.clone(), 1.into());
+            assert_eq!(&rand / UBigNumber::one(), rand);
+            assert_eq!(&rand / &rand, UBigNumber::one());
 
-            let twice = rand.clone() * 2.into();
+            let twice = &rand * 2u64;
             let quotient = twice / rand;
 
-            assert_eq!(quotient, 2.into());
+            assert_eq!(quotient, 2u64);
 
         }
 
         {
             let dividend: UBigNumber = "0x55589105C1E8687FEDA2729CA4FBD7DF".into();
             let divisor: UBigNumber = "0x21906BFD894BDCD7F0F5A4CC17554F5F".into();
-            let correct_quotient: UBigNumber = 2.into();
+            let correct_quotient: UBigNumber = 2u64.into();
             let correct_remainder: UBigNumber = "1237b90aaf50aed00bb7290476513921".into();
-            assert_eq!(correct_quotient, dividend.clone() / divisor.clone());
+            assert_eq!(correct_quotient, &dividend / &divisor);
             assert_eq!(correct_remainder, dividend % divisor);
         }
 
@@ -130,7 +878,7 @@ mod tests {
             let divisor: UBigNumber = "0x63861802BBE83994FAA714D6517E1784".into();
             let correct_quotient: UBigNumber = "149099cbfc9ba7b38".into();
             let correct_remainder: UBigNumber = "24919ff5ff5ce6f9ca0f48bfac46c682".into();
-            assert_eq!(correct_quotient, dividend.clone() / divisor.clone());
+            assert_eq!(correct_quotient, &dividend / &divisor);
             assert_eq!(correct_remainder, dividend % divisor);
         }
         
@@ -141,7 +889,7 @@ mod tests {
             let divisor: UBigNumber = "0xEC3857AB7272481CFC9E4B7A828EFB861B005130E5F2F301".into();
             let correct_quotient: UBigNumber = "8d41ebf26d8e036e2180bfa06594cea61ab6cba1834e249f3d06a9d3f2e07700ac2a7984db2956c2267ab39b9656d3e68338034da2edc0e".into();
             let correct_remainder: UBigNumber = "9e1e5b3c0fd14f30866c9465fc8dae310053b8bab03c5ad7".into();
-            assert_eq!(correct_quotient, dividend.clone() / divisor.clone());
+            assert_eq!(correct_quotient, &dividend / &divisor);
             assert_eq!(correct_remainder, dividend % divisor);
         }
 
@@ -149,8 +897,8 @@ mod tests {
             let dividend: UBigNumber = "0x40000000000000000000000000000000000000000000000000000000000000113FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF855".into();
             let divisor: UBigNumber = "0x2000000000000000000000000000000000000000000000000000000000000009B".into();
             let correct_quotient: UBigNumber = "1ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffef".into();
-            let correct_remainder: UBigNumber = 0x2A0.into();
-            assert_eq!(correct_quotient, dividend.clone() / divisor.clone());
+            let correct_remainder: UBigNumber = 0x2A0u64.into();
+            assert_eq!(correct_quotient, &dividend / &divisor);
             assert_eq!(correct_remainder, dividend % divisor);
         }
 
@@ -170,4 +918,161 @@ mod tests {
 
     }
 
+    #[test]
+    fn test_division_corrections() {
+
+        // The 64-bit version of a case from Hacker's Delight where the quotient word estimate is too big and has to
+        // be corrected by adding the divisor back.
+        {
+            let dividend = UBigNumber::from_words(vec![0, 0, 1 << 63, 0x7FFFFFFFFFFFFFFF]);
+            let divisor = UBigNumber::from_words(vec![1, 0, 1 << 63]);
+            let (q, r) = dividend.quotient_and_remainder(&divisor);
+            assert_eq!(q, UBigNumber::from_words(vec![Word::MAX - 1]));
+            assert_eq!(r, UBigNumber::from_words(vec![2, Word::MAX, 0x7FFFFFFFFFFFFFFF]));
+        }
+
+        // Words near the edges are the ones that make the estimate go wrong, so build numbers out of those.
+        let mut rng = thread_rng();
+        let edge_words = [0, 1, 2, Word::MAX, Word::MAX - 1, 1 << 63, (1 << 63) - 1, (1 << 63) + 1];
+        let mut edge_number = |len: usize| {
+            let mut words: Vec<Word> = (0..len).map(|_| edge_words[rng.gen_range(0..edge_words.len())]).collect();
+            words[len - 1] |= 1;
+            UBigNumber::from_words(words)
+        };
+
+        for _ in 0..500 {
+            let dividend = edge_number(8);
+            let divisor = edge_number(3);
+            let (q, r) = dividend.quotient_and_remainder(&divisor);
+            assert!(r < divisor);
+            assert_eq!(divisor * q + r, dividend);
+        }
+    }
+
+    #[test]
+    fn test_recursive_division() {
+        let mut rng = thread_rng();
+        let threshold = int_utility::BURNIKEL_ZIEGLER_THRESHOLD;
+
+        for _ in 0..10 {
+            let divisor = UBigNumber::rand(rng.gen_range(threshold..(3 * threshold)));
+            let dividend = UBigNumber::rand(divisor.len() + rng.gen_range(threshold..(4 * threshold)));
+            let (q, r) = dividend.quotient_and_remainder(&divisor);
+            assert!(r < divisor);
+            assert_eq!(divisor * q + r, dividend);
+        }
+
+        // a divisor whose top bit is already set, so no normalization shift is needed
+        let mut divisor = UBigNumber::rand(2 * threshold + 1);
+        divisor.words[2 * threshold] |= 1 << 63;
+        let dividend = UBigNumber::rand(5 * threshold);
+        let (q, r) = dividend.quotient_and_remainder(&divisor);
+        assert!(r < divisor);
+        assert_eq!(divisor * q + r, dividend);
+    }
+
+    #[test]
+    fn test_borrowed_operators() {
+        let a = UBigNumber::rand(5);
+        let b = UBigNumber::rand(3);
+
+        assert_eq!(&a + &b, a.clone() + b.clone());
+        assert_eq!(&a + b.clone(), a.clone() + &b);
+        assert_eq!(&a - &b, a.clone() - b.clone());
+        assert_eq!(&a * &b, a.clone() * b.clone());
+        assert_eq!(&a / &b, a.clone() / b.clone());
+        assert_eq!(&a % &b, a.clone() % b.clone());
+        assert_eq!(&a | &b, a.clone() | b.clone());
+        assert_eq!(&a << 7, a.clone() << 7);
+        assert_eq!(&a >> 7, a.clone() >> 7);
+
+        let mut c = a.clone();
+        c *= &b;
+        c += &b;
+        c /= &b;
+        assert_eq!(c, &a + UBigNumber::one());
+        c -= &a;
+        assert_eq!(c, UBigNumber::one());
+
+        let x = BigNumber::from_sign_magnitude(true, a.clone());
+        let y = BigNumber::from_ubn(b.clone());
+
+        assert_eq!(&x + &y, BigNumber::from_sign_magnitude(true, &a - &b));
+        assert_eq!(&y + &x, x.clone() + y.clone());
+        assert_eq!(&x - &y, BigNumber::from_sign_magnitude(true, &a + &b));
+        assert_eq!(&y - &x, BigNumber::from_ubn(&a + &b));
+        assert_eq!(&x * &y, BigNumber::from_sign_magnitude(true, &a * &b));
+        assert_eq!(&x / &y, BigNumber::from_sign_magnitude(true, &a / &b));
+        assert_eq!(&x % &y, BigNumber::from_sign_magnitude(true, &a % &b));
+        assert_eq!(&x - &x, BigNumber::zero());
+        assert_eq!(-&x, BigNumber::from_ubn(a));
+    }
+
+    #[test]
+    fn test_primitive_operators() {
+        let a = UBigNumber::rand(4);
+        let big_a = BigNumber::from_ubn(a.clone());
+
+        assert_eq!(&a + 5u8, &a + UBigNumber::from_int(5));
+        assert_eq!(&a - 5u16, &a - UBigNumber::from_int(5));
+        assert_eq!(&a * 5u32, &a * UBigNumber::from_int(5));
+        assert_eq!(&a / 5usize, &a / UBigNumber::from_int(5));
+        assert_eq!(&a % 5u64, &a % UBigNumber::from_int(5));
+        assert_eq!(7u64 + &a, &a + 7u64);
+
+        // u128 is the only one that can take more than one word
+        let wide = u128::MAX - 12345;
+        let wide_ubn = UBigNumber::from_words(vec![wide as Word, (wide >> 64) as Word]);
+        assert_eq!(&a * wide, &a * &wide_ubn);
+        assert_eq!(&a / wide, &a / &wide_ubn);
+        assert_eq!(&a % wide, &a % &wide_ubn);
+        assert_eq!(wide_ubn, wide);
+        assert!(wide_ubn > u64::MAX && wide_ubn < u128::MAX);
+        assert!(5u8 < a && a != 5u8);
+
+        let mut b = UBigNumber::from_int(Word::MAX);
+        b += 1u8;
+        assert_eq!(b, UBigNumber::from_words(vec![0, 1]));
+        b -= 1u8;
+        assert_eq!(b, Word::MAX);
+        b *= 0u32;
+        assert_eq!(b, 0u32);
+
+        // signed primitives on a BigNumber
+        assert_eq!(&big_a + -5, &big_a - 5);
+        assert_eq!(&big_a * -5, -(&big_a * 5i32));
+        assert_eq!(&big_a / -5i64, -BigNumber::from_ubn(&a / 5u64));
+        assert_eq!(&big_a % -5i64, BigNumber::from_ubn(&a % 5u64));
+        assert_eq!(-&big_a % 5, -BigNumber::from_ubn(&a % 5u64));
+        assert_eq!(3 - BigNumber::from_ubn(UBigNumber::from_int(10)), -7);
+        assert_eq!(BigNumber::zero() - 1, -1);
+        assert_eq!(BigNumber::zero() - 1 + 1, 0);
+        assert!(-&big_a < i128::MIN && big_a > i128::MAX);
+        assert!(BigNumber::zero() - 3 < -2 && -2 > BigNumber::zero() - 3);
+        assert_eq!(BigNumber::zero() + i128::MIN, i128::MIN);
+
+        // signed primitives, and so untyped literals, on a UBigNumber
+        assert_eq!(&a * 2, &a + &a);
+        assert_eq!(&a + -5, &a - 5u8);
+        assert_eq!(&a - -5i128, &a + 5u8);
+        assert_eq!(&a / 7, &a / 7u8);
+        assert_eq!(&a % -7, &a % 7u8);
+        assert_eq!(2 * &a, &a * 2u8);
+        assert_eq!(UBigNumber::zero() * -3, 0);
+        assert!(a > -1 && a > i128::MIN && -1 < a && a != -1);
+
+        let mut y = UBigNumber::from_int(6);
+        y -= 1;
+        assert!(y == 5);
+        y += -5;
+        assert_eq!(y, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to subtract with overflow")]
+    #[cfg(debug_assertions)]
+    fn test_negative_primitive() {
+        let _ = UBigNumber::from_int(3) * -1;
+    }
+
 }
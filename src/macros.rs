@@ -0,0 +1,267 @@
+//! Macros for implementing the operator traits for every combination of owned and borrowed operands, given one
+//! implementation of the assigning operator taking a borrowed right hand side, and for implementing the conversions
+//! to and from every primitive integer type.
+
+/// Implements `$op_assign<$t>` and `$op` for `$t op $t`, `$t op &$t`, `&$t op $t` and `&$t op &$t` in terms of
+/// `$op_assign<&$t>`. 
+/// 
+/// With `commutative`, `&$t op $t` reuses the right hand side instead of cloning the left hand side.
+macro_rules! forward_binop {
+	(impl $op:ident, $method:ident, $op_assign:ident, $method_assign:ident for $t:ty) => {
+		forward_binop!(@common impl $op, $method, $op_assign, $method_assign for $t);
+
+		impl std::ops::$op<$t> for &$t {
+			type Output = $t;
+
+			fn $method(self, rhs: $t) -> Self::Output {
+				let mut result = self.clone();
+				std::ops::$op_assign::$method_assign(&mut result, &rhs);
+				result
+			}
+		}
+	};
+
+	(impl commutative $op:ident, $method:ident, $op_assign:ident, $method_assign:ident for $t:ty) => {
+		forward_binop!(@common impl $op, $method, $op_assign, $method_assign for $t);
+
+		impl std::ops::$op<$t> for &$t {
+			type Output = $t;
+
+			fn $method(self, mut rhs: $t) -> Self::Output {
+				std::ops::$op_assign::$method_assign(&mut rhs, self);
+				rhs
+			}
+		}
+	};
+
+	(@common impl $op:ident, $method:ident, $op_assign:ident, $method_assign:ident for $t:ty) => {
+		impl std::ops::$op_assign<$t> for $t {
+			fn $method_assign(&mut self, rhs: $t) {
+				std::ops::$op_assign::$method_assign(self, &rhs)
+			}
+		}
+
+		impl std::ops::$op<$t> for $t {
+			type Output = $t;
+
+			fn $method(mut self, rhs: $t) -> Self::Output {
+				std::ops::$op_assign::$method_assign(&mut self, &rhs);
+				self
+			}
+		}
+
+		impl std::ops::$op<&$t> for $t {
+			type Output = $t;
+
+			fn $method(mut self, rhs: &$t) -> Self::Output {
+				std::ops::$op_assign::$method_assign(&mut self, rhs);
+				self
+			}
+		}
+
+		impl std::ops::$op<&$t> for &$t {
+			type Output = $t;
+
+			fn $method(self, rhs: &$t) -> Self::Output {
+				let mut result = self.clone();
+				std::ops::$op_assign::$method_assign(&mut result, rhs);
+				result
+			}
+		}
+	};
+}
+
+/// Implements `ShlAssign`, `ShrAssign`, `Shl` and `Shr` for `$t` and `&$t`, shifting by each primitive integer type
+/// `$amount`. Like the primitive integers, shifting by a negative amount panics.
+/// 
+/// `$t` needs to have the methods `shift_left` and `shift_right`, which take the shift amount as a `u64`.
+macro_rules! forward_shifts {
+	(impl for $t:ty, $($amount:ty),*) => {
+		$(
+			impl std::ops::ShlAssign<$amount> for $t {
+				fn shl_assign(&mut self, rhs: $amount) {
+					self.shift_left(forward_shifts!(@amount rhs, "left"))
+				}
+			}
+
+			impl std::ops::ShrAssign<$amount> for $t {
+				fn shr_assign(&mut self, rhs: $amount) {
+					self.shift_right(forward_shifts!(@amount rhs, "right"))
+				}
+			}
+
+			impl std::ops::Shl<$amount> for $t {
+				type Output = $t;
+
+				fn shl(mut self, rhs: $amount) -> Self::Output {
+					self <<= rhs;
+					self
+				}
+			}
+
+			impl std::ops::Shl<$amount> for &$t {
+				type Output = $t;
+
+				fn shl(self, rhs: $amount) -> Self::Output {
+					let mut shifted = self.clone();
+					shifted <<= rhs;
+					shifted
+				}
+			}
+
+			impl std::ops::Shr<$amount> for $t {
+				type Output = $t;
+
+				fn shr(mut self, rhs: $amount) -> Self::Output {
+					self >>= rhs;
+					self
+				}
+			}
+
+			impl std::ops::Shr<$amount> for &$t {
+				type Output = $t;
+
+				fn shr(self, rhs: $amount) -> Self::Output {
+					let mut shifted = self.clone();
+					shifted >>= rhs;
+					shifted
+				}
+			}
+		)*
+	};
+
+	// Any amount too big for a `u64` is much too big to shift left by anyways, and shifts right by it give zero
+	(@amount $rhs:expr, $direction:literal) => {
+		match crate::primitive::Primitive::sign_magnitude($rhs) {
+			(true, _) => panic!(concat!("attempt to shift ", $direction, " by a negative amount")),
+			(false, magnitude) => u64::try_from(magnitude).unwrap_or(u64::MAX),
+		}
+	};
+}
+
+/// Implements the arithmetic, assignment and comparison operators between `$t` and each primitive integer type
+/// `$prim`, in both orders.
+/// 
+/// `$t` needs to have the methods `add_primitive`, `sub_primitive`, `mul_primitive`, `div_primitive`,
+/// `rem_primitive` and `cmp_primitive`, which take the sign and magnitude of the primitive, and `from_primitive`,
+/// which is used when the primitive is on the left.
+macro_rules! forward_primitive_ops {
+	(impl for $t:ty, $($prim:ty),*) => {
+		$(
+			forward_primitive_ops!(@op Add, add, AddAssign, add_assign, add_primitive, $t, $prim);
+			forward_primitive_ops!(@op Sub, sub, SubAssign, sub_assign, sub_primitive, $t, $prim);
+			forward_primitive_ops!(@op Mul, mul, MulAssign, mul_assign, mul_primitive, $t, $prim);
+			forward_primitive_ops!(@op Div, div, DivAssign, div_assign, div_primitive, $t, $prim);
+			forward_primitive_ops!(@op Rem, rem, RemAssign, rem_assign, rem_primitive, $t, $prim);
+
+			impl PartialEq<$prim> for $t {
+				fn eq(&self, other: &$prim) -> bool {
+					self.cmp_primitive(crate::primitive::Primitive::sign_magnitude(*other)) == std::cmp::Ordering::Equal
+				}
+			}
+
+			impl PartialOrd<$prim> for $t {
+				fn partial_cmp(&self, other: &$prim) -> Option<std::cmp::Ordering> {
+					Some(self.cmp_primitive(crate::primitive::Primitive::sign_magnitude(*other)))
+				}
+			}
+
+			impl PartialEq<$t> for $prim {
+				fn eq(&self, other: &$t) -> bool {
+					other == self
+				}
+			}
+
+			impl PartialOrd<$t> for $prim {
+				fn partial_cmp(&self, other: &$t) -> Option<std::cmp::Ordering> {
+					other.partial_cmp(self).map(std::cmp::Ordering::reverse)
+				}
+			}
+		)*
+	};
+
+	(@op $op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $primitive_method:ident, $t:ty, $prim:ty) => {
+		impl std::ops::$op_assign<$prim> for $t {
+			fn $method_assign(&mut self, rhs: $prim) {
+				self.$primitive_method(crate::primitive::Primitive::sign_magnitude(rhs))
+			}
+		}
+
+		impl std::ops::$op<$prim> for $t {
+			type Output = $t;
+
+			fn $method(mut self, rhs: $prim) -> Self::Output {
+				std::ops::$op_assign::$method_assign(&mut self, rhs);
+				self
+			}
+		}
+
+		impl std::ops::$op<$prim> for &$t {
+			type Output = $t;
+
+			fn $method(self, rhs: $prim) -> Self::Output {
+				let mut result = self.clone();
+				std::ops::$op_assign::$method_assign(&mut result, rhs);
+				result
+			}
+		}
+
+		impl std::ops::$op<$t> for $prim {
+			type Output = $t;
+
+			fn $method(self, rhs: $t) -> Self::Output {
+				std::ops::$op::$method(<$t>::from_primitive(self), &rhs)
+			}
+		}
+
+		impl std::ops::$op<&$t> for $prim {
+			type Output = $t;
+
+			fn $method(self, rhs: &$t) -> Self::Output {
+				std::ops::$op::$method(<$t>::from_primitive(self), rhs)
+			}
+		}
+	};
+}
+
+/// Implements `From<$prim>` for `$t` for every primitive listed, using `$t::from_primitive`
+macro_rules! forward_from_primitive {
+	(impl for $t:ty, $($prim:ty),*) => {
+		$(
+			impl From<$prim> for $t {
+				fn from(value: $prim) -> Self {
+					<$t>::from_primitive(value)
+				}
+			}
+		)*
+	};
+}
+
+/// Implements `TryFrom<$t>` and `TryFrom<&$t>` for every primitive listed, which fail with an `OutOfRangeError` if
+/// the number doesn't fit.
+/// 
+/// `$t` needs to have the method `to_sign_magnitude`, which gives the sign and magnitude of the number if the
+/// magnitude fits in a `u128`.
+macro_rules! forward_try_into_primitive {
+	(impl for $t:ty, $($prim:ty),*) => {
+		$(
+			impl TryFrom<&$t> for $prim {
+				type Error = crate::error::OutOfRangeError;
+
+				fn try_from(value: &$t) -> Result<Self, Self::Error> {
+					value.to_sign_magnitude()
+						.and_then(|(is_negative, magnitude)| crate::primitive::Primitive::from_sign_magnitude(is_negative, magnitude))
+						.ok_or(crate::error::OutOfRangeError::new())
+				}
+			}
+
+			impl TryFrom<$t> for $prim {
+				type Error = crate::error::OutOfRangeError;
+
+				fn try_from(value: $t) -> Result<Self, Self::Error> {
+					<$prim>::try_from(&value)
+				}
+			}
+		)*
+	};
+}
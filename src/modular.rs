@@ -0,0 +1,288 @@
+//! Modular arithmetic on big numbers, including contexts for doing lots of arithmetic with the same modulus.
+
+use crate::{bignumber::BigNumber, int_utility, pow::sliding_window_pow, ubignumber::{UBigNumber, Word, WORD_BIT_COUNT}};
+
+impl UBigNumber {
+
+	/// `self^exponent mod modulus`
+	/// 
+	/// For odd moduli, all of the multiplications are done in Montgomery form, so there's no division until the end.
+	/// Even moduli use Barrett reduction instead.
+	/// 
+	/// Panics if the modulus is zero.
+	pub fn modpow(&self, exponent: &UBigNumber, modulus: &UBigNumber) -> UBigNumber {
+		if modulus.is_zero() {
+			panic!("Division by zero")
+		} else if *modulus == UBigNumber::one() {
+			UBigNumber::zero()
+		} else if exponent.is_zero() {
+			UBigNumber::one()
+		} else if modulus.bit(0) {
+			let context = MontgomeryContext::new(modulus);
+			let mut power = vec![0; context.word_len()];
+			context.pow(&mut power, &context.to_mont(self), exponent);
+			context.from_mont(&power)
+		} else {
+			BarrettContext::new(modulus).pow_mod(self, exponent)
+		}
+	}
+
+	/// The inverse of this number modulo `modulus`, in `[0, modulus)`, or `None` if this number and the modulus aren't
+	/// coprime
+	/// 
+	/// Powers of two (like the `R` of Montgomery form) get their own faster algorithm. Anything else goes through
+	/// the extended Euclidean algorithm.
+	/// 
+	/// Panics if the modulus is zero.
+	pub fn mod_inverse(&self, modulus: &UBigNumber) -> Option<UBigNumber> {
+		if modulus.is_zero() {
+			panic!("Division by zero")
+		} else if *modulus == UBigNumber::one() {
+			Some(UBigNumber::zero())
+		} else if modulus.is_power_of_two() {
+			self.bit(0).then(|| self.power_of_two_inverse(modulus.bit_length() - 1))
+		} else {
+			let (g, x, _) = self.extended_gcd(modulus);
+			(g == UBigNumber::one()).then(|| x.euc_rem(BigNumber::from(modulus.clone())).magnitude)
+		}
+	}
+
+	/// The inverse of this odd number modulo `2^bits`, by Newton's iteration (Hensel lifting). If `x` is an inverse
+	/// modulo `2^k`, then `x * (2 - self * x)` is an inverse modulo `2^2k`, so starting from the inverse of the lowest
+	/// word, each step doubles the number of correct bits.
+	fn power_of_two_inverse(&self, bits: u64) -> UBigNumber {
+		debug_assert!(self.bit(0));
+
+		let low_bits = |x: UBigNumber, n: u64| {
+			let mut mask = UBigNumber::zero();
+			mask.set_bit(n, true);
+			x & (mask - 1u8)
+		};
+
+		let mut inverse = UBigNumber::from(int_utility::word_inverse(self[0]));
+		let mut precision = WORD_BIT_COUNT as u64;
+
+		while precision < bits {
+			precision = (2 * precision).min(bits);
+
+			// 2 - self * x is negative, so it's taken modulo 2^precision by adding 2^precision first
+			let product = low_bits(&inverse * &low_bits(self.clone(), precision), precision);
+			let mut correction = UBigNumber::from(2u8);
+			correction.set_bit(precision, true);
+			inverse = low_bits(&inverse * &(correction - product), precision);
+		}
+
+		low_bits(inverse, bits)
+	}
+}
+
+impl BigNumber {
+
+	/// `self^exponent mod modulus`, in `[0, |modulus|)`. A negative exponent means a power of the inverse of this
+	/// number, so this is `None` if the exponent is negative and this number has no inverse.
+	/// 
+	/// Panics if the modulus is zero.
+	pub fn modpow(&self, exponent: &BigNumber, modulus: &BigNumber) -> Option<BigNumber> {
+		let base = if exponent.is_negative {
+			self.mod_inverse(modulus)?
+		} else {
+			self.euc_rem(BigNumber::from(modulus.magnitude.clone())).magnitude
+		};
+
+		Some(BigNumber::from(base.modpow(&exponent.magnitude, &modulus.magnitude)))
+	}
+
+	/// The inverse of this number modulo `|modulus|`, in `[0, |modulus|)`, or `None` if this number and the modulus
+	/// aren't coprime
+	/// 
+	/// Panics if the modulus is zero.
+	pub fn mod_inverse(&self, modulus: &BigNumber) -> Option<UBigNumber> {
+		if modulus.magnitude.is_zero() {
+			panic!("Division by zero")
+		}
+
+		self.euc_rem(BigNumber::from(modulus.magnitude.clone())).magnitude.mod_inverse(&modulus.magnitude)
+	}
+}
+
+// MARK: Montgomery Form
+
+/// Precomputed constants for doing lots of arithmetic modulo one odd number in Montgomery form, where `x` is
+/// represented by `x * R mod m` for `R = B^n` and an `n` word modulus. Multiplying two numbers in this form only
+/// needs multiplications and shifts, no division.
+/// 
+/// Numbers in Montgomery form are word buffers of exactly `word_len()` words. `mul` and `square` write into buffers
+/// the caller provides, so they never allocate. `to_mont`, `from_mont` and `pow` do allocate, since they're only
+/// called a few times in a row of operations.
+#[derive(Debug, Clone)]
+pub struct MontgomeryContext {
+	modulus: Vec<Word>,
+
+	/// `-modulus^-1 mod B`
+	neg_inv: Word,
+
+	/// `R^2 mod modulus`, which takes a number into Montgomery form with one multiplication
+	r_squared: Vec<Word>,
+}
+
+impl MontgomeryContext {
+
+	/// Sets up the constants for an odd modulus bigger than one
+	/// 
+	/// Panics if the modulus is even or one.
+	pub fn new(modulus: &UBigNumber) -> MontgomeryContext {
+		assert!(modulus.bit(0) && *modulus != UBigNumber::one(), "Montgomery form needs an odd modulus bigger than one");
+
+		let n = modulus.len();
+		let r_squared = (UBigNumber::one() << (2 * n * WORD_BIT_COUNT)) % modulus;
+
+		MontgomeryContext {
+			modulus: modulus.words.clone(),
+			neg_inv: int_utility::word_inverse(modulus[0]).wrapping_neg(),
+			r_squared: padded(&r_squared, n),
+		}
+	}
+
+	/// The modulus
+	pub fn modulus(&self) -> UBigNumber {
+		UBigNumber::from_words(self.modulus.clone())
+	}
+
+	/// The number of words in a number in Montgomery form, which is the length of the modulus
+	pub fn word_len(&self) -> usize {
+		self.modulus.len()
+	}
+
+	/// Puts a number into Montgomery form, reducing it first if it's not less than the modulus
+	pub fn to_mont(&self, x: &UBigNumber) -> Vec<Word> {
+		let reduced = if int_utility::cmp_words(&x.words, &self.modulus).is_lt() { x.clone() } else { x % &self.modulus() };
+		let mut mont = vec![0; self.word_len()];
+		self.mul(&mut mont, &padded(&reduced, self.word_len()), &self.r_squared);
+		mont
+	}
+
+	/// Takes a number out of Montgomery form
+	pub fn from_mont(&self, x: &[Word]) -> UBigNumber {
+		// multiplying by 1 takes away the extra factor of R
+		let mut one = vec![0; self.word_len()];
+		one[0] = 1;
+		let mut normal = vec![0; self.word_len()];
+		self.mul(&mut normal, x, &one);
+		UBigNumber::from_words(normal)
+	}
+
+	/// Writes the product of two numbers in Montgomery form into `out`
+	pub fn mul(&self, out: &mut [Word], lhs: &[Word], rhs: &[Word]) {
+		int_utility::montgomery_mul(out, lhs, rhs, &self.modulus, self.neg_inv);
+	}
+
+	/// Writes the square of a number in Montgomery form into `out`, using `scratch` to hold the whole square before
+	/// it's reduced. `scratch` must be `2 * word_len()` words, and what's left in it afterwards doesn't mean anything.
+	pub fn square(&self, out: &mut [Word], x: &[Word], scratch: &mut [Word]) {
+		int_utility::montgomery_square(out, x, &self.modulus, self.neg_inv, scratch);
+	}
+
+	/// Writes `base^exponent` into `out`, where the base and the result are both in Montgomery form
+	pub fn pow(&self, out: &mut [Word], base: &[Word], exponent: &UBigNumber) {
+		if exponent.is_zero() {
+			out.copy_from_slice(&self.to_mont(&UBigNumber::one()));
+			return;
+		}
+
+		// Each operation gets its own scratch buffer, and copies its result back. Squaring also needs room for the
+		// whole square.
+		let (mut square_scratch, mut mul_scratch) = (vec![0; self.word_len()], vec![0; self.word_len()]);
+		let mut product = vec![0; 2 * self.word_len()];
+		let power = sliding_window_pow(&base.to_vec(), exponent,
+			|acc| {
+				self.square(&mut square_scratch, acc, &mut product);
+				acc.copy_from_slice(&square_scratch);
+			},
+			|acc, power| {
+				self.mul(&mut mul_scratch, acc, power);
+				acc.copy_from_slice(&mul_scratch);
+			}
+		);
+		out.copy_from_slice(&power);
+	}
+}
+
+// MARK: Barrett Reduction
+
+/// Precomputed constants for reducing lots of numbers modulo one number with Barrett reduction, which replaces the
+/// division with two multiplications by precomputing `floor(B^2k / m)` for a `k` word modulus. Unlike Montgomery form,
+/// this works for any modulus and the numbers don't need to be converted.
+#[derive(Debug, Clone)]
+pub struct BarrettContext {
+	modulus: UBigNumber,
+
+	/// `floor(B^2k / modulus)`
+	mu: UBigNumber,
+}
+
+impl BarrettContext {
+
+	/// Sets up the constants for a modulus
+	/// 
+	/// Panics if the modulus is zero.
+	pub fn new(modulus: &UBigNumber) -> BarrettContext {
+		if modulus.is_zero() {
+			panic!("Division by zero")
+		}
+
+		let mut b_2k = UBigNumber::one();
+		b_2k.shl_words(2 * modulus.len());
+
+		BarrettContext { modulus: modulus.clone(), mu: b_2k / modulus }
+	}
+
+	/// The modulus
+	pub fn modulus(&self) -> &UBigNumber {
+		&self.modulus
+	}
+
+	/// `x mod m`. This is fast for anything less than `B^2k`, like the product of two reduced numbers, and anything
+	/// bigger than that falls back to a division.
+	pub fn reduce(&self, x: &UBigNumber) -> UBigNumber {
+		let k = self.modulus.len();
+		if x.len() > 2 * k {
+			return x % &self.modulus;
+		}
+
+		// q = floor(floor(x / B^(k - 1)) * mu / B^(k + 1)) is at most two less than floor(x / m)
+		let mut q = x.clone();
+		q.shr_words(k - 1);
+		q *= &self.mu;
+		q.shr_words(k + 1);
+
+		let mut r = x - &(q * &self.modulus);
+		while r >= self.modulus {
+			r -= &self.modulus;
+		}
+		r
+	}
+
+	/// `lhs * rhs mod m`
+	pub fn mul_mod(&self, lhs: &UBigNumber, rhs: &UBigNumber) -> UBigNumber {
+		self.reduce(&(self.reduce(lhs) * self.reduce(rhs)))
+	}
+
+	/// `base^exponent mod m`
+	pub fn pow_mod(&self, base: &UBigNumber, exponent: &UBigNumber) -> UBigNumber {
+		if exponent.is_zero() {
+			return self.reduce(&UBigNumber::one());
+		}
+
+		sliding_window_pow(&self.reduce(base), exponent,
+			|acc| *acc = self.reduce(&acc.square()),
+			|acc, power| *acc = self.reduce(&(&*acc * power))
+		)
+	}
+}
+
+/// The words of a number, padded with zeroes to `len` words
+fn padded(x: &UBigNumber, len: usize) -> Vec<Word> {
+	let mut words = x.words.clone();
+	words.resize(len, 0);
+	words
+}
@@ -0,0 +1,127 @@
+//! Raising big numbers to powers.
+
+use crate::{bignumber::BigNumber, ubignumber::{UBigNumber, Word}};
+
+impl UBigNumber {
+
+	/// This number to the power of `exponent`, where `0^0 == 1`
+	pub fn pow(&self, exponent: u64) -> UBigNumber {
+		self.pow_ubn(&UBigNumber::from(exponent))
+	}
+
+	/// This number to the power of a big exponent, where `0^0 == 1`. Unless this is zero or one, the exponent had
+	/// better not be very big at all.
+	pub fn pow_ubn(&self, exponent: &UBigNumber) -> UBigNumber {
+		if exponent.is_zero() {
+			UBigNumber::one()
+		} else if self.is_zero() || *self == UBigNumber::one() {
+			self.clone()
+		} else {
+			sliding_window_pow(self, exponent, UBigNumber::square_assign, |acc, power| *acc *= power)
+		}
+	}
+
+	/// This number to the power of `exponent`, or `None` if the result would have more than `max_bits` bits. Results
+	/// that are obviously too big are caught before doing any of the work.
+	pub fn checked_pow(&self, exponent: u64, max_bits: u64) -> Option<UBigNumber> {
+		// The result has more than (bit_length - 1) * exponent bits
+		let lower_bound = (self.bit_length().saturating_sub(1) as u128) * exponent as u128;
+		if self.bit_length() > 1 && lower_bound >= max_bits as u128 {
+			return None;
+		}
+
+		let power = self.pow(exponent);
+		if power.bit_length() <= max_bits { Some(power) } else { None }
+	}
+}
+
+impl BigNumber {
+
+	/// This number to the power of `exponent`, where `0^0 == 1`
+	pub fn pow(&self, exponent: u64) -> BigNumber {
+		BigNumber::from_sign_magnitude(self.is_negative && exponent % 2 == 1, self.magnitude.pow(exponent))
+	}
+
+	/// This number to the power of a big exponent, where `0^0 == 1`
+	pub fn pow_ubn(&self, exponent: &UBigNumber) -> BigNumber {
+		BigNumber::from_sign_magnitude(self.is_negative && exponent.bit(0), self.magnitude.pow_ubn(exponent))
+	}
+
+	/// This number to the power of `exponent`, or `None` if the magnitude of the result would have more than
+	/// `max_bits` bits
+	pub fn checked_pow(&self, exponent: u64, max_bits: u64) -> Option<BigNumber> {
+		let magnitude = self.magnitude.checked_pow(exponent, max_bits)?;
+		Some(BigNumber::from_sign_magnitude(self.is_negative && exponent % 2 == 1, magnitude))
+	}
+}
+
+/// The number of exponent bits to handle at once in sliding window exponentiation, which trades the cost of
+/// precomputing `2^(k - 1)` odd powers against the number of multiplications saved
+pub(crate) fn window_size(exponent_bits: u64) -> u32 {
+	match exponent_bits {
+		0..=8 => 1,
+		9..=24 => 3,
+		25..=80 => 4,
+		81..=240 => 5,
+		_ => 6,
+	}
+}
+
+/// Left to right sliding window exponentiation, for anything with a multiplication and a squaring. The exponent
+/// can't be zero.
+/// 
+/// The exponent is read from the top down in windows of at most `k` bits that start and end with a one, so that the
+/// base only ever gets multiplied by its odd powers, which are precomputed. The zeroes between windows are just
+/// squarings.
+pub(crate) fn sliding_window_pow<T: Clone>(base: &T, exponent: &UBigNumber, mut square: impl FnMut(&mut T), mut mul: impl FnMut(&mut T, &T)) -> T {
+	debug_assert!(!exponent.is_zero());
+
+	let k = window_size(exponent.bit_length());
+
+	// odd_powers[i] = base^(2i + 1)
+	let mut odd_powers = vec![base.clone()];
+	if k > 1 {
+		let mut base_squared = base.clone();
+		square(&mut base_squared);
+		for i in 1..(1 << (k - 1)) {
+			let mut next = odd_powers[i - 1].clone();
+			mul(&mut next, &base_squared);
+			odd_powers.push(next);
+		}
+	}
+
+	let mut result: Option<T> = None;
+	let mut i = exponent.bit_length() as i64 - 1;
+
+	while i >= 0 {
+		if !exponent.bit(i as u64) {
+			if let Some(acc) = result.as_mut() {
+				square(acc);
+			}
+			i -= 1;
+			continue;
+		}
+
+		// The longest window starting at bit i of at most k bits that ends in a one
+		let mut j = (i - k as i64 + 1).max(0);
+		while !exponent.bit(j as u64) {
+			j += 1;
+		}
+
+		let window = (j..=i).rev().fold(0 as Word, |w, b| (w << 1) | exponent.bit(b as u64) as Word);
+
+		match result.as_mut() {
+			Some(acc) => {
+				for _ in j..=i {
+					square(acc);
+				}
+				mul(acc, &odd_powers[(window >> 1) as usize]);
+			},
+			None => result = Some(odd_powers[(window >> 1) as usize].clone()),
+		}
+
+		i = j - 1;
+	}
+
+	result.unwrap()
+}
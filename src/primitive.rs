@@ -0,0 +1,51 @@
+//! Glue for the operators and conversions between big numbers and the primitive integer types.
+
+/// A primitive integer type. Every one of these can be split into a sign and a magnitude of at most two words.
+pub(crate) trait Primitive: Copy {
+
+	/// The sign (true if negative) and magnitude of this integer
+	fn sign_magnitude(self) -> (bool, u128);
+
+	/// The integer with the given sign and magnitude, if it's in range of this type
+	fn from_sign_magnitude(is_negative: bool, magnitude: u128) -> Option<Self>;
+}
+
+macro_rules! impl_primitive_unsigned {
+	($($t:ty),*) => {
+		$(
+			impl Primitive for $t {
+				fn sign_magnitude(self) -> (bool, u128) {
+					(false, self as u128)
+				}
+
+				fn from_sign_magnitude(is_negative: bool, magnitude: u128) -> Option<Self> {
+					if is_negative && magnitude != 0 {
+						None
+					} else {
+						<$t>::try_from(magnitude).ok()
+					}
+				}
+			}
+		)*
+	};
+}
+
+macro_rules! impl_primitive_signed {
+	($($t:ty),*) => {
+		$(
+			impl Primitive for $t {
+				fn sign_magnitude(self) -> (bool, u128) {
+					(self < 0, self.unsigned_abs() as u128)
+				}
+
+				fn from_sign_magnitude(is_negative: bool, magnitude: u128) -> Option<Self> {
+					let value = if is_negative { 0i128.checked_sub_unsigned(magnitude)? } else { i128::try_from(magnitude).ok()? };
+					<$t>::try_from(value).ok()
+				}
+			}
+		)*
+	};
+}
+
+impl_primitive_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_primitive_signed!(i8, i16, i32, i64, i128, isize);
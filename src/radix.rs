@@ -0,0 +1,353 @@
+//! Converting big numbers to and from strings of digits in any radix from 2 to 36.
+//! 
+//! When the radix is a power of two, the digits are just slices of the bits. Otherwise, for big numbers, both
+//! directions are divide-and-conquer: a number is split in half (by dividing by a big power of the radix) and each
+//! half is converted separately, so the cost of conversion follows the cost of multiplication and division instead
+//! of being quadratic.
+
+use std::{fmt::{Binary, Display, Formatter, LowerHex, Octal, UpperHex}, str::FromStr};
+
+use crate::{bignumber::BigNumber, error::{ParseBigNumberError, ParseBigNumberErrorKind}, ubignumber::{UBigNumber, Word, WORD_BIT_COUNT}};
+
+/// The size (in words or chunks) below which conversion uses the simple quadratic algorithms
+const RADIX_CONVERSION_THRESHOLD: usize = 30;
+
+impl UBigNumber {
+
+	/// Creates a UBigNumber from a string of decimal digits
+	/// 
+	/// Panics if there is anything other than the digits 0-9 in the string.
+	pub fn from_dec_string(dec_str: &str) -> UBigNumber {
+		UBigNumber::from_str_radix(dec_str, 10).unwrap_or_else(|e| panic!("Invalid decimal string: {}", e))
+	}
+
+	/// The decimal representation of this number
+	pub fn to_dec_string(&self) -> String {
+		self.to_str_radix(10)
+	}
+
+	/// Parses a string of digits in the given radix, where the digits past 9 are letters (of either case). Unlike
+	/// parsing with `FromStr`, there can't be a prefix, separators or whitespace.
+	/// 
+	/// Panics if the radix isn't in `2..=36`.
+	pub fn from_str_radix(digits: &str, radix: u32) -> Result<UBigNumber, ParseBigNumberError> {
+		assert!((2..=36).contains(&radix), "Radix must be in 2..=36, not {}", radix);
+		Ok(UBigNumber::from_digit_values(&digit_values(digits, radix, 0, false)?, radix))
+	}
+
+	/// The digits of this number in the given radix, using lowercase letters for the digits past 9
+	/// 
+	/// Panics if the radix isn't in `2..=36`.
+	pub fn to_str_radix(&self, radix: u32) -> String {
+		assert!((2..=36).contains(&radix), "Radix must be in 2..=36, not {}", radix);
+
+		if radix.is_power_of_two() {
+			self.to_digits_string_pow2(radix.trailing_zeros())
+		} else {
+			self.to_digits_string(radix)
+		}
+	}
+
+	/// Creates a UBigNumber from the values of its digits in the given radix, most significant first
+	fn from_digit_values(digits: &[u8], radix: u32) -> UBigNumber {
+		if radix.is_power_of_two() {
+			UBigNumber::from_digits_pow2(digits, radix.trailing_zeros())
+		} else {
+			UBigNumber::from_digits(digits, radix)
+		}
+	}
+
+	/// Creates a UBigNumber from its digits in the given radix, most significant first. Every digit must be less
+	/// than the radix.
+	fn from_digits(digits: &[u8], radix: u32) -> UBigNumber {
+		let (chunk, chunk_digits) = radix_chunk(radix);
+
+		// Group the digits into chunks, which are the digits in base `chunk`, least significant first
+		let chunks: Vec<Word> = digits.rchunks(chunk_digits).map(|c| {
+			c.iter().fold(0, |acc, d| acc * radix as Word + *d as Word)
+		}).collect();
+
+		let powers = chunk_powers(chunk, chunks.len());
+		combine_chunks(&chunks, chunk, &powers)
+	}
+
+	/// Creates a UBigNumber from its digits in the radix `2^bits`, most significant first, by just placing the bits of
+	/// each digit where they go.
+	fn from_digits_pow2(digits: &[u8], bits: u32) -> UBigNumber {
+		let mut words = vec![0; (digits.len() * bits as usize).div_ceil(WORD_BIT_COUNT)];
+
+		for (i, d) in digits.iter().rev().enumerate() {
+			let position = i * bits as usize;
+			let (word, shift) = (position / WORD_BIT_COUNT, position % WORD_BIT_COUNT);
+			words[word] |= (*d as Word) << shift;
+
+			// digits like octal ones can straddle two words
+			if shift + bits as usize > WORD_BIT_COUNT {
+				words[word + 1] |= (*d as Word) >> (WORD_BIT_COUNT - shift);
+			}
+		}
+
+		UBigNumber::from_words(words)
+	}
+
+	/// The digits of this number in the given radix, as a string (using lowercase letters past 9)
+	fn to_digits_string(&self, radix: u32) -> String {
+		let (chunk, chunk_digits) = radix_chunk(radix);
+
+		// Only the powers that are no bigger than this number are useful for splitting it
+		let mut powers = vec![UBigNumber::from_int(chunk)];
+		loop {
+			let next = powers[powers.len() - 1].square();
+			if next > *self {
+				break;
+			}
+			powers.push(next);
+		}
+
+		let mut digits = String::new();
+		write_chunks(self, radix, chunk_digits, &powers, 0, &mut digits);
+		digits
+	}
+
+	/// The digits of this number in the radix `2^bits`, read straight off of the bits from the top down
+	fn to_digits_string_pow2(&self, bits: u32) -> String {
+		let digit_count = (self.bit_length() as usize).div_ceil(bits as usize).max(1);
+		let mask: Word = (1 << bits) - 1;
+
+		(0..digit_count).rev().map(|i| {
+			let position = i * bits as usize;
+			let (word, shift) = (position / WORD_BIT_COUNT, position % WORD_BIT_COUNT);
+			let mut d = self.safe_word(word) >> shift;
+			if shift + bits as usize > WORD_BIT_COUNT {
+				d |= self.safe_word(word + 1) << (WORD_BIT_COUNT - shift);
+			}
+			std::char::from_digit((d & mask) as u32, 1 << bits).unwrap()
+		}).collect()
+	}
+}
+
+impl BigNumber {
+
+	/// Parses a string of digits in the given radix with an optional leading `-` or `+` (see
+	/// `UBigNumber::from_str_radix`)
+	/// 
+	/// Panics if the radix isn't in `2..=36`.
+	pub fn from_str_radix(digits: &str, radix: u32) -> Result<BigNumber, ParseBigNumberError> {
+		assert!((2..=36).contains(&radix), "Radix must be in 2..=36, not {}", radix);
+
+		let (is_negative, rest) = split_sign(digits);
+		let values = digit_values(rest, radix, digits.len() - rest.len(), false)?;
+		Ok(BigNumber::from_sign_magnitude(is_negative, UBigNumber::from_digit_values(&values, radix)))
+	}
+
+	/// The digits of this number in the given radix, with a leading `-` if it's negative
+	/// 
+	/// Panics if the radix isn't in `2..=36`.
+	pub fn to_str_radix(&self, radix: u32) -> String {
+		let digits = self.magnitude.to_str_radix(radix);
+		if self.is_negative { format!("-{}", digits) } else { digits }
+	}
+}
+
+// MARK: Parsing
+
+impl FromStr for UBigNumber {
+	type Err = ParseBigNumberError;
+
+	/// Parses a number in decimal, or in hex, octal or binary with a `0x`, `0o` or `0b` prefix. The digits can be
+	/// separated by underscores and the whole thing can be surrounded by whitespace.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let trimmed = s.trim();
+		let offset = s.len() - s.trim_start().len();
+
+		let (radix, digits) = split_radix_prefix(trimmed)?;
+		let values = digit_values(digits, radix, offset + trimmed.len() - digits.len(), true)?;
+		Ok(UBigNumber::from_digit_values(&values, radix))
+	}
+}
+
+impl FromStr for BigNumber {
+	type Err = ParseBigNumberError;
+
+	/// Parses a number just like a `UBigNumber` is parsed, but with an optional `-` or `+` before any radix prefix
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let trimmed = s.trim();
+		let offset = s.len() - s.trim_start().len();
+
+		let (is_negative, unsigned) = split_sign(trimmed);
+		let (radix, digits) = split_radix_prefix(unsigned)?;
+		let values = digit_values(digits, radix, offset + trimmed.len() - digits.len(), true)?;
+		Ok(BigNumber::from_sign_magnitude(is_negative, UBigNumber::from_digit_values(&values, radix)))
+	}
+}
+
+/// Splits a leading `-` or `+` off of a string, returning whether the number is negative
+fn split_sign(s: &str) -> (bool, &str) {
+	match s.strip_prefix('-') {
+		Some(rest) => (true, rest),
+		None => (false, s.strip_prefix('+').unwrap_or(s)),
+	}
+}
+
+/// Splits a `0x`, `0o` or `0b` prefix (of either case) off of a string, returning the radix it stands for. Strings
+/// with no prefix are decimal.
+fn split_radix_prefix(s: &str) -> Result<(u32, &str), ParseBigNumberError> {
+	let mut chars = s.chars();
+	match (chars.next(), chars.next()) {
+		(Some('0'), Some('x' | 'X')) => Ok((16, &s[2..])),
+		(Some('0'), Some('o' | 'O')) => Ok((8, &s[2..])),
+		(Some('0'), Some('b' | 'B')) => Ok((2, &s[2..])),
+		(Some('0'), Some(c)) if c.is_alphabetic() => Err(ParseBigNumberError::new(ParseBigNumberErrorKind::InvalidRadixPrefix)),
+		_ => Ok((10, s)),
+	}
+}
+
+/// The values of the digits in a string, most significant first. `offset` is where the string starts in the whole
+/// string being parsed, so that errors point to the right place. If `separators` is set, underscores between digits
+/// are skipped.
+fn digit_values(s: &str, radix: u32, offset: usize, separators: bool) -> Result<Vec<u8>, ParseBigNumberError> {
+	let mut values = Vec::with_capacity(s.len());
+
+	for (i, c) in s.char_indices() {
+		match c.to_digit(radix) {
+			Some(d) => values.push(d as u8),
+			None if separators && c == '_' => (),
+			None => return Err(ParseBigNumberError::new(ParseBigNumberErrorKind::InvalidDigit { position: offset + i, digit: c })),
+		}
+	}
+
+	if values.is_empty() {
+		Err(ParseBigNumberError::new(ParseBigNumberErrorKind::Empty))
+	} else {
+		Ok(values)
+	}
+}
+
+// MARK: Chunked Conversion
+
+/// The biggest power of the radix that fits in a word, and its exponent
+fn radix_chunk(radix: u32) -> (Word, usize) {
+	debug_assert!((2..=36).contains(&radix));
+
+	let (mut chunk, mut chunk_digits): (Word, usize) = (radix as Word, 1);
+	while let Some(next) = chunk.checked_mul(radix as Word) {
+		(chunk, chunk_digits) = (next, chunk_digits + 1);
+	}
+	(chunk, chunk_digits)
+}
+
+/// `powers[k] = chunk^(2^k)`, for every `k` with `2^k < len`
+fn chunk_powers(chunk: Word, len: usize) -> Vec<UBigNumber> {
+	let mut powers = vec![UBigNumber::from_int(chunk)];
+	while 1 << powers.len() < len {
+		let next = powers[powers.len() - 1].square();
+		powers.push(next);
+	}
+	powers
+}
+
+/// Computes the number with the given digits in base `chunk`, least significant first, by computing the top and
+/// bottom halves separately and then putting them together with one multiplication.
+fn combine_chunks(chunks: &[Word], chunk: Word, powers: &[UBigNumber]) -> UBigNumber {
+	if chunks.len() <= RADIX_CONVERSION_THRESHOLD {
+		let mut value = UBigNumber::zero();
+		for c in chunks.iter().rev() {
+			value *= chunk;
+			value += *c;
+		}
+		value
+	} else {
+		// split at the biggest power of two below the length, so that we have the power to shift the top half by
+		let k = (chunks.len() - 1).ilog2() as usize;
+		let (low, high) = chunks.split_at(1 << k);
+		combine_chunks(high, chunk, powers) * &powers[k] + combine_chunks(low, chunk, powers)
+	}
+}
+
+/// Writes the digits of `n` onto the end of `digits`. If `width` isn't zero, the digits are padded with zeroes to
+/// exactly that many digits.
+/// 
+/// `powers[k]` must be `chunk^(2^k)`, where the chunk is the biggest power of the radix that fits in a word.
+fn write_chunks(n: &UBigNumber, radix: u32, chunk_digits: usize, powers: &[UBigNumber], width: usize, digits: &mut String) {
+	if n.len() <= RADIX_CONVERSION_THRESHOLD || powers.len() == 1 {
+		// Chip off chunks from the bottom, which are at most one word each
+		let mut chunks = vec![];
+		let mut rest = n.clone();
+		while !rest.is_zero() {
+			let (q, r) = rest.div_rem_word(powers[0][0]);
+			chunks.push(r);
+			rest = q;
+		}
+
+		let mut chunk_strings: Vec<String> = chunks.iter().map(|c| digits_of_word(*c, radix)).collect();
+		let top_len = chunk_strings.last().map_or(0, |s| s.len());
+		let len = top_len + chunk_digits * chunks.len().saturating_sub(1);
+
+		if width > len {
+			digits.extend(std::iter::repeat_n('0', width - len));
+		} else if width == 0 && chunks.is_empty() {
+			chunk_strings.push(String::from("0"));
+		}
+
+		for (i, s) in chunk_strings.iter().enumerate().rev() {
+			if i + 1 < chunks.len() {
+				digits.extend(std::iter::repeat_n('0', chunk_digits - s.len()));
+			}
+			digits.push_str(s);
+		}
+	} else {
+		// Split by the biggest power that isn't bigger than n, so that the bottom part is exactly as many digits
+		// as that power has zeroes
+		let k = powers.iter().rposition(|p| p <= n).unwrap_or(0);
+		let (high, low) = n.quotient_and_remainder(&powers[k]);
+		let low_width = chunk_digits << k;
+
+		if high.is_zero() {
+			write_chunks(&low, radix, chunk_digits, &powers[..k.max(1)], width, digits);
+		} else {
+			write_chunks(&high, radix, chunk_digits, powers, width.saturating_sub(low_width), digits);
+			write_chunks(&low, radix, chunk_digits, &powers[..k.max(1)], low_width, digits);
+		}
+	}
+}
+
+/// The digits of a single word in the given radix, with no padding
+fn digits_of_word(mut word: Word, radix: u32) -> String {
+	let mut reversed = vec![];
+	loop {
+		reversed.push(std::char::from_digit((word % radix as Word) as u32, radix).unwrap());
+		word /= radix as Word;
+		if word == 0 {
+			break;
+		}
+	}
+	reversed.iter().rev().collect()
+}
+
+// MARK: Formatting
+
+/// Implements a formatting trait by writing the digits of the magnitude in the given radix, which lets the formatter
+/// handle the sign, the `#` prefix and all of the padding just like it does for primitive integers.
+macro_rules! impl_radix_fmt {
+	($trait:ident, $radix:expr, $prefix:expr, $upper:expr) => {
+		impl $trait for UBigNumber {
+			fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+				let digits = self.to_str_radix($radix);
+				f.pad_integral(true, $prefix, &if $upper { digits.to_uppercase() } else { digits })
+			}
+		}
+
+		impl $trait for BigNumber {
+			fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+				let digits = self.magnitude.to_str_radix($radix);
+				f.pad_integral(!self.is_negative, $prefix, &if $upper { digits.to_uppercase() } else { digits })
+			}
+		}
+	};
+}
+
+impl_radix_fmt!(Display, 10, "", false);
+impl_radix_fmt!(LowerHex, 16, "0x", false);
+impl_radix_fmt!(UpperHex, 16, "0x", true);
+impl_radix_fmt!(Octal, 8, "0o", false);
+impl_radix_fmt!(Binary, 2, "0b", false);
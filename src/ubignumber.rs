@@ -1,8 +1,8 @@
-use std::{cmp::max, fmt::Debug, io::StderrLock, ops::{Add, AddAssign, BitOr, BitOrAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Range, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign}};
+use std::{cmp::max, fmt::Debug, ops::{AddAssign, BitAndAssign, BitOrAssign, BitXorAssign, DivAssign, Index, IndexMut, MulAssign, Range, RemAssign, SubAssign}};
 use algebra_kit::algebra::{EuclideanDomain, Ring};
 use rand::Rng;
 
-use crate::int_utility;
+use crate::{int_utility, primitive::Primitive};
 
 pub type Word = u64;
 
@@ -39,50 +39,17 @@ impl UBigNumber {
 		UBigNumber::from_words(words)
 	}
 
-	/// Creates a UBigNumber from a string
+	/// Creates a UBigNumber from a hexadecimal string, with or without a "0x" prefix
 	/// 
-	/// The string must be already sanitized, meaning if it is a hexadecimal string, there is no "0x" prefix.
-	pub fn from_hex_string(hex_str: &str,) -> UBigNumber {
+	/// Panics if there is anything other than hex digits after the prefix. Use `str::parse` to handle bad input.
+	pub fn from_hex_string(hex_str: &str) -> UBigNumber {
+		let digits = hex_str.strip_prefix("0x").or_else(|| hex_str.strip_prefix("0X")).unwrap_or(hex_str);
 
-		// The amount of hex digits that take up one Word
-		let word_str_len = WORD_BYTE_COUNT * 2; // 2 hex characters per byte
-
-		// The following is somewhat generated by ChatGPT. I asked it to implement
-		// this function and it slightly failed. So, I'm just taking what worked,
-		// and changing it to be a bit better :)
-
-		// --- BEGIN AI CODE ---
-
-		// Step 1: Strip optional "0x" prefix
-        let hex_str = if hex_str.starts_with("0x") || hex_str.starts_with("0X") {
-            &hex_str[2..]
-        } else {
-            hex_str
-        };
-
-        let hex_str = if hex_str.len() % word_str_len != 0 {
-			let zeroes = (0..(word_str_len - (hex_str.len() % word_str_len))).map(|_| "0").collect::<String>();
-            format!("{}{}", zeroes, hex_str)
-        } else {
-            hex_str.to_string()
-        };
-
-		// --- END AI CODE ---
-
-		// Now, for each clump of 16, we map that to a word.
-
-		debug_assert!(hex_str.len() % word_str_len == 0);
-
-		let new_len = hex_str.len() / word_str_len;
-		let mut words = vec![0 ; new_len];
-
-		for i in 0..new_len {
-			words[new_len - i - 1] = Word::from_str_radix(&hex_str[(word_str_len * i)..(word_str_len * i + word_str_len)], 16).unwrap();
+		if digits.is_empty() {
+			return UBigNumber::zero();
 		}
 
-		let mut ubn = UBigNumber::from_words(words);
-		ubn.normalize();
-		ubn
+		UBigNumber::from_str_radix(digits, 16).unwrap_or_else(|e| panic!("Invalid hex string: {}", e))
 	}
 
 	/// Creates a UBigNumber from given words
@@ -177,7 +144,7 @@ impl UBigNumber {
 	// MARK: Arithmetic Helpers
 
 	/// Adds another `UBN` to this `UBN`
-	fn custom_add(&mut self, rhs: UBigNumber, handle_overflow: bool) {
+	fn custom_add(&mut self, rhs: &UBigNumber, handle_overflow: bool) {
 		let required_size = if handle_overflow { max(self.len(), rhs.len()) + 1 } else { self.len() };
 
 		self.extend(required_size);
@@ -191,124 +158,485 @@ impl UBigNumber {
 		self.normalize();
 	}
 
-	/// Computes the quotient and remainder when dividing by a single 64-bit digit
-	fn div_rem_short(dividend: UBigNumber, divisor: Word) -> (UBigNumber, Word) {
-		let mut partial_remainder = 0;
+	/// Computes the quotient and remainder when dividing by a single word
+	pub fn div_rem_word(&self, divisor: Word) -> (UBigNumber, Word) {
+		let mut quotient = self.clone();
+		let remainder = quotient.div_rem_word_assign(divisor);
+		(quotient, remainder)
+	}
+
+	/// Divides this number by a single word in place, returning the remainder
+	fn div_rem_word_assign(&mut self, divisor: Word) -> Word {
+		if divisor == 0 {
+			panic!("Division by zero")
+		}
+
+		let mut remainder = 0;
+		for i in (0..self.len()).rev() {
+			(_, self[i], remainder) = int_utility::div_wide(remainder, self[i], divisor);
+		}
+
+		self.normalize();
+		remainder
+	}
+
+	/// Adds a single word to this number in place
+	fn add_word(&mut self, rhs: Word) {
+		if int_utility::add_assign_words(&mut self.words, &[rhs]) {
+			self.words.push(1);
+		}
+	}
+
+	/// Subtracts a single word from this number in place. Just like subtracting a `UBigNumber`, this panics in debug
+	/// builds if the result would be negative, and wraps around otherwise.
+	fn sub_word(&mut self, rhs: Word) {
+		let borrow = int_utility::sub_assign_words(&mut self.words, &[rhs]);
+		debug_assert!(!borrow, "attempt to subtract with overflow");
+		self.normalize();
+	}
+
+	/// Multiplies this number by a single word in place
+	fn mul_word(&mut self, rhs: Word) {
+		let mut carry = 0;
+		for w in self.words.iter_mut() {
+			(*w, carry) = int_utility::addmul(*w, rhs, carry, 0);
+		}
+
+		if carry != 0 {
+			self.words.push(carry);
+		}
+		self.normalize();
+	}
+
+	/// Long division by a divisor of at least two words, using Knuth's Algorithm D (TAOCP Vol. 2, 4.3.1).
+	/// 
+	/// The dividend must be bigger than the divisor.
+	fn div_rem_core(dividend: UBigNumber, divisor: UBigNumber) -> (UBigNumber, UBigNumber) {
+		let n = divisor.len();
+		let m = dividend.len() - n;
+		debug_assert!(n >= 2);
+
+		// D1: Normalize, so the top bit of the divisor is set. Now the estimate of each quotient word from the top
+		// words is never too small, and after the refinement below it's at most one too big.
+		let shift = divisor.msw().leading_zeros();
+		let mut v = int_utility::shl_bits(&divisor.words, shift);
+		v.truncate(n);
+		let mut u = int_utility::shl_bits(&dividend.words, shift);
+
+		let (v_first, v_second) = (v[n - 1] as u128, v[n - 2] as u128);
+		let mut quotient_words = vec![0 ; m + 1];
+
+		for j in (0..=m).rev() {
+
+			// D3: Estimate the quotient word from the top two words of what's left of the dividend, and refine
+			// it using the next word of each.
+			let top = ((u[j + n] as u128) << WORD_BIT_COUNT) | u[j + n - 1] as u128;
+			let mut qhat = top / v_first;
+			let mut rhat = top % v_first;
+
+			while qhat > Word::MAX as u128 || qhat * v_second > ((rhat << WORD_BIT_COUNT) | u[j + n - 2] as u128) {
+				qhat -= 1;
+				rhat += v_first;
+				if rhat > Word::MAX as u128 {
+					break;
+				}
+			}
+
+			// D4: Multiply and subtract, in place
+			let borrow = int_utility::mul_sub_assign_words(&mut u[j..=(j + n)], &v, qhat as Word);
 
-		let mut quotient_words = vec![0 ; dividend.len()];
+			// D5, D6: If that went negative, qhat was one too big, so add one divisor back. After the refinement
+			// this can only be needed once, and only very rarely.
+			if borrow {
+				qhat -= 1;
+				int_utility::add_assign_words(&mut u[j..=(j + n)], &v);
+			}
 
-		let mut starting_digit_offset = 0;
+			quotient_words[j] = qhat as Word;
+		}
 
-		let mut partial_dividend = if divisor > dividend[dividend.len() - 1] {
-			starting_digit_offset = 1;
-			UBigNumber::from_words(vec![dividend[dividend.len() - 2], dividend[dividend.len() - 1]])
+		// D8: Unnormalize the remainder
+		let remainder_words = int_utility::shr_bits(&u[..n], shift);
+
+		(UBigNumber::from_words(quotient_words), UBigNumber::from_words(remainder_words))
+	}
+	
+	/// Computes the square of this number. This is faster than multiplying it by itself.
+	pub fn square(&self) -> UBigNumber {
+		UBigNumber::from_words(int_utility::square(&self.words))
+	}
+
+	/// Squares this number in place
+	pub fn square_assign(&mut self) {
+		self.words = int_utility::square(&self.words);
+		self.normalize()
+	}
+
+	/// Returns a sub-integer, the interger represented by a selected range of the words of this UBigNumber.
+	/// For example, if the (base 2^64) digits of this number are bn = 439803, then bn[2..=4] == 398
+	pub fn sub_number(&self, range: Range<usize>) -> UBigNumber {
+		let slice = &self.words[range];
+		let sub_vec = slice.to_vec();
+		UBigNumber::from_words(sub_vec)
+	}
+
+	pub fn quotient_and_remainder(&self, divisor: &Self) -> (Self, Self) {
+		if divisor.is_zero() {
+			panic!("Division by zero")
+		} else if divisor.len() >= int_utility::BURNIKEL_ZIEGLER_THRESHOLD && self.len() >= divisor.len() + int_utility::BURNIKEL_ZIEGLER_THRESHOLD {
+			UBigNumber::div_rem_recursive(self, divisor)
 		} else {
-			UBigNumber::from_int(dividend[dividend.len() - 1])
-		};
+			UBigNumber::div_rem_base(self, divisor)
+		}
+	}
 
-		for j in (0..(dividend.len() - starting_digit_offset)).rev() {
+	/// Division using the schoolbook algorithms, for when the operands are too small for recursive division
+	fn div_rem_base(dividend: &UBigNumber, divisor: &UBigNumber) -> (UBigNumber, UBigNumber) {
+		if dividend < divisor {
+			(UBigNumber::zero(), dividend.clone())
+		} else if dividend == divisor {
+			(UBigNumber::one(), UBigNumber::zero())
+		} else if divisor.len() == 1 {
+			let (q, r) = dividend.div_rem_word(divisor[0]);
+			(q, r.into())
+		} else {
+			UBigNumber::div_rem_core(dividend.clone(), divisor.clone())
+		}
+	}
 
-			debug_assert!(partial_dividend.len() <= 2);
+	// MARK: Checked Arithmetic
 
-			let (_, qhat, _) = int_utility::div_wide(partial_dividend.safe_word(1), partial_dividend[0], divisor);
-			quotient_words[j] = qhat;
+	/// Subtracts `rhs` from this number, returning whether the result would have been negative. If it would have,
+	/// the result wraps around modulo `B^n`, where `n` is the length of the longer operand.
+	pub fn overflowing_sub(&self, rhs: &UBigNumber) -> (UBigNumber, bool) {
+		let mut difference = self.clone();
+		let borrow = difference.overflowing_sub_assign(rhs);
+		(difference, borrow)
+	}
 
-			let partial_product = UBigNumber::from_int(divisor) * qhat.into();
-			partial_remainder = (partial_dividend - partial_product)[0];
+	/// `self - rhs`, or `None` if that would be negative
+	pub fn checked_sub(&self, rhs: &UBigNumber) -> Option<UBigNumber> {
+		match self.overflowing_sub(rhs) {
+			(difference, false) => Some(difference),
+			(_, true) => None,
+		}
+	}
 
-			if j == 0 { break; }
+	/// `self - rhs`, or zero if that would be negative
+	pub fn saturating_sub(&self, rhs: &UBigNumber) -> UBigNumber {
+		self.checked_sub(rhs).unwrap_or_else(UBigNumber::zero)
+	}
 
-			partial_dividend = UBigNumber::from_words(vec![dividend[j - 1], partial_remainder]);
+	/// `|self - rhs|`
+	pub fn abs_diff(&self, rhs: &UBigNumber) -> UBigNumber {
+		if self >= rhs {
+			self - rhs
+		} else {
+			rhs - self
 		}
+	}
 
-		(UBigNumber::from_words(quotient_words), partial_remainder)
+	/// Subtracts `rhs` from this number in place, wrapping around and returning true if the result would be negative
+	fn overflowing_sub_assign(&mut self, rhs: &UBigNumber) -> bool {
+		self.extend(rhs.len());
+		let borrow = int_utility::sub_assign_words(&mut self.words, &rhs.words);
+		self.normalize();
+		borrow
 	}
 
-	fn div_rem_core(dividend: UBigNumber, divisor: UBigNumber) -> (UBigNumber, UBigNumber) {
+	// MARK: Bits
+
+	/// Whether bit `n` of this number is set, where bit 0 is the least significant
+	pub fn bit(&self, n: u64) -> bool {
+		let word = (n / WORD_BIT_COUNT as u64) as usize;
+		self.safe_word(word) & (1 << (n % WORD_BIT_COUNT as u64)) != 0
+	}
 
-		// This is a REALLY BAD implementation.
-		let k = dividend.len() - divisor.len() + 1;
-		let mut quotient_words = vec![0 ; k];
+	/// Sets or clears bit `n` of this number, where bit 0 is the least significant
+	pub fn set_bit(&mut self, n: u64, value: bool) {
+		let word = (n / WORD_BIT_COUNT as u64) as usize;
+		let mask: Word = 1 << (n % WORD_BIT_COUNT as u64);
 
-		let mut first_digit_offset = 0;
+		if value {
+			self.extend(word + 1);
+			self[word] |= mask;
+		} else if word < self.len() {
+			self[word] &= !mask;
+			self.normalize();
+		}
+	}
 
-		let mut partial_dividend = if divisor > dividend.sub_number((k - 1)..dividend.len()) {
-			first_digit_offset = 1;
-			dividend.sub_number((k - 2)..dividend.len())
+	/// The number of bits needed to write this number, which is zero for zero
+	pub fn bit_length(&self) -> u64 {
+		(self.len() * WORD_BIT_COUNT) as u64 - self.leading_zeros() as u64
+	}
+
+	/// The number of bits that are set
+	pub fn count_ones(&self) -> u64 {
+		self.words.iter().map(|w| w.count_ones() as u64).sum()
+	}
+
+	/// The number of zero bits below the lowest set bit, or `None` for zero, which has no set bits
+	pub fn trailing_zeros(&self) -> Option<u64> {
+		let word = self.words.iter().position(|w| *w != 0)?;
+		Some((word * WORD_BIT_COUNT) as u64 + self[word].trailing_zeros() as u64)
+	}
+
+	/// The number of leading zeros in the most significant word, which is all 64 of them for zero
+	pub fn leading_zeros(&self) -> u32 {
+		self.msw().leading_zeros()
+	}
+
+	/// Whether this number is `2^k` for some `k`
+	pub fn is_power_of_two(&self) -> bool {
+		self.msw().is_power_of_two() && self.words[..self.len() - 1].iter().all(|w| *w == 0)
+	}
+
+	/// The smallest power of two that is at least this number
+	pub fn next_power_of_two(&self) -> UBigNumber {
+		if self.is_zero() {
+			UBigNumber::one()
+		} else if self.is_power_of_two() {
+			self.clone()
 		} else {
-			dividend.sub_number((k - 1)..dividend.len())
-		};
+			let mut power = UBigNumber::zero();
+			power.set_bit(self.bit_length(), true);
+			power
+		}
+	}
 
-		let mut partial_remainder: UBigNumber = 0.into();
+	// MARK: Shifts
 
-		for j in (0..(k - first_digit_offset)).rev() {
-			
-			// we want to divide into the partial dividend!
-			let u_first = partial_dividend.safe_word(divisor.len());
-			let u_second = partial_dividend.safe_word(divisor.len() - 1);
+	/// Multiplies this number by B^n in place, by shifting whole words
+	pub fn shl_words(&mut self, n: usize) {
+		if !self.is_zero() {
+			self.words.splice(0..0, std::iter::repeat_n(0, n));
+		}
+	}
 
-			// println!("Partial dividend: {:?}", partial_dividend);
+	/// Divides this number by B^n in place, rounding down, by shifting whole words
+	pub fn shr_words(&mut self, n: usize) {
+		self.words.drain(0..n.min(self.len()));
+		self.normalize();
+	}
 
-			let v_first = divisor[divisor.len() - 1];
-			// println!("Doing long division: {:?} {:?} / {:?}", u_first, u_second, v_first);
+	/// Multiplies this number by 2^n in place
+	fn shift_left(&mut self, n: u64) {
+		let bits = (n % WORD_BIT_COUNT as u64) as u32;
+		if bits != 0 {
+			self.words = int_utility::shl_bits(&self.words, bits);
+			self.normalize();
+		}
+		self.shl_words(usize::try_from(n / WORD_BIT_COUNT as u64).expect("Shift is too big"));
+	}
 
-			let (q_hi, q_lo, _) = int_utility::div_wide(u_first, u_second, v_first);
-			let mut qhat = UBigNumber::from_words(vec![q_lo, q_hi]);
+	/// Divides this number by 2^n in place, rounding down
+	fn shift_right(&mut self, n: u64) {
+		self.shr_words(usize::try_from(n / WORD_BIT_COUNT as u64).unwrap_or(usize::MAX));
+		let bits = (n % WORD_BIT_COUNT as u64) as u32;
+		if bits != 0 {
+			self.words = int_utility::shr_bits(&self.words, bits);
+			self.normalize();
+		}
+	}
 
-			// println!("Result of long division is {:?}", qhat);
+	// MARK: Primitive Arithmetic
 
-			
+	/// Promotes a primitive integer to a `UBigNumber`. A negative one is treated just like subtracting from zero, so
+	/// it panics in debug builds and wraps around otherwise.
+	pub(crate) fn from_primitive<P: Primitive>(int: P) -> UBigNumber {
+		let (is_negative, magnitude) = int.sign_magnitude();
+		let mut value = UBigNumber::from_u128(magnitude);
+		if is_negative {
+			value.negate();
+		}
+		value
+	}
 
-			let mut partial_product = divisor.clone() * qhat.clone().into();
+	fn from_u128(int: u128) -> UBigNumber {
+		UBigNumber::from_words(vec![int as Word, (int >> WORD_BIT_COUNT) as Word])
+	}
 
-			while partial_product > partial_dividend {
-				qhat -= 1.into();
-				partial_product -= divisor.clone();
-			}
+	/// Replaces this number with `0 - self`, which panics in debug builds unless it's zero, just like any other
+	/// subtraction that would be negative
+	fn negate(&mut self) {
+		*self = UBigNumber::zero() - &*self;
+	}
 
-			debug_assert_eq!(qhat.safe_word(1), 0);
+	// These all take the sign and magnitude of a primitive. Adding or subtracting a negative primitive is the same as
+	// subtracting or adding its magnitude, and a negative result from multiplying or dividing by one is handled like
+	// any other negative result. They use the single word fast paths whenever the magnitude fits in one word, which
+	// is always unless the primitive is 128 bits.
 
-			quotient_words[j] = qhat[0];
+	fn add_primitive(&mut self, (is_negative, magnitude): (bool, u128)) {
+		match (is_negative, Word::try_from(magnitude)) {
+			(true, _) => self.sub_primitive((false, magnitude)),
+			(false, Ok(word)) => self.add_word(word),
+			(false, Err(_)) => *self += &UBigNumber::from_u128(magnitude)
+		}
+	}
 
-			partial_remainder = partial_dividend.clone() - partial_product;
+	fn sub_primitive(&mut self, (is_negative, magnitude): (bool, u128)) {
+		match (is_negative, Word::try_from(magnitude)) {
+			(true, _) => self.add_primitive((false, magnitude)),
+			(false, Ok(word)) => self.sub_word(word),
+			(false, Err(_)) => *self -= &UBigNumber::from_u128(magnitude)
+		}
+	}
 
-			if j == 0 { break; }
+	fn mul_primitive(&mut self, (is_negative, magnitude): (bool, u128)) {
+		match Word::try_from(magnitude) {
+			Ok(word) => self.mul_word(word),
+			Err(_) => *self *= &UBigNumber::from_u128(magnitude)
+		}
+		if is_negative {
+			self.negate();
+		}
+	}
 
-			partial_dividend = partial_remainder.clone();
-			partial_dividend.words.insert(0, dividend[j - 1]);
+	fn div_primitive(&mut self, (is_negative, magnitude): (bool, u128)) {
+		match Word::try_from(magnitude) {
+			Ok(word) => { self.div_rem_word_assign(word); },
+			Err(_) => *self /= &UBigNumber::from_u128(magnitude)
+		}
+		if is_negative {
+			self.negate();
+		}
+	}
 
+	/// The remainder takes the sign of the dividend, like it does for the primitives, so the sign of the divisor
+	/// doesn't matter
+	fn rem_primitive(&mut self, (_, magnitude): (bool, u128)) {
+		match Word::try_from(magnitude) {
+			Ok(word) => self.words = vec![self.div_rem_word_assign(word)],
+			Err(_) => *self %= &UBigNumber::from_u128(magnitude)
 		}
+	}
 
+	fn cmp_primitive(&self, (is_negative, magnitude): (bool, u128)) -> std::cmp::Ordering {
+		match self.to_sign_magnitude() {
+			_ if is_negative => std::cmp::Ordering::Greater,
+			Some((_, value)) => value.cmp(&magnitude),
+			None => std::cmp::Ordering::Greater,
+		}
+	}
 
-		(UBigNumber::from_words(quotient_words), partial_remainder)
+	/// The sign (never negative) and value of this number, if it fits in a `u128`
+	pub(crate) fn to_sign_magnitude(&self) -> Option<(bool, u128)> {
+		if self.len() > 2 {
+			None
+		} else {
+			Some((false, ((self.safe_word(1) as u128) << WORD_BIT_COUNT) | self.safe_word(0) as u128))
+		}
+	}
+
+	// MARK: Recursive Division
 
+	/// Burnikel-Ziegler recursive division ("Fast Recursive Division", 1998).
+	/// 
+	/// The dividend is cut into blocks the size of the divisor, which are divided in from the top just like long
+	/// division with one "digit" per block. Each of those steps is a 2n-by-n division, which is done with two
+	/// 3n-by-2n divisions, each of which is done with a n-by-n/2 division and a multiplication. So, the cost of
+	/// division follows the cost of multiplication.
+	fn div_rem_recursive(dividend: &UBigNumber, divisor: &UBigNumber) -> (UBigNumber, UBigNumber) {
+
+		// The quotient estimates in `div_3n_2n` need the top bit of the divisor to be set
+		let shift = divisor.msw().leading_zeros();
+		let divisor = UBigNumber::from_words(int_utility::shl_bits(&divisor.words, shift));
+		let dividend = UBigNumber::from_words(int_utility::shl_bits(&dividend.words, shift));
+		let n = divisor.len();
+
+		let mut quotient = UBigNumber::zero();
+		let mut remainder = UBigNumber::zero();
+
+		for block in dividend.words.chunks(n).rev() {
+			remainder.shl_words(n);
+			remainder += UBigNumber::from_words(block.to_vec());
+
+			let (q, r) = UBigNumber::div_2n_1n(remainder, &divisor, n);
+
+			quotient.shl_words(n);
+			quotient += q;
+			remainder = r;
+		}
+
+		(quotient, UBigNumber::from_words(int_utility::shr_bits(&remainder.words, shift)))
 	}
-	
-	/// Returns a sub-integer, the interger represented by a selected range of the words of this UBigNumber.
-	/// For example, if the (base 2^64) digits of this number are bn = 439803, then bn[2..=4] == 398
-	pub fn sub_number(&self, range: Range<usize>) -> UBigNumber {
-		let slice = &self.words[range];
-		let sub_vec = slice.to_vec();
-		UBigNumber::from_words(sub_vec)
+
+	/// Divides `a` by `b`, where `b` has `n` words with the top bit set and `a < b * B^n`
+	fn div_2n_1n(mut a: UBigNumber, b: &UBigNumber, n: usize) -> (UBigNumber, UBigNumber) {
+		if n < int_utility::BURNIKEL_ZIEGLER_THRESHOLD {
+			return UBigNumber::div_rem_base(&a, b);
+		}
+
+		// The halves need to be the same size, so pad odd sizes by multiplying both by B
+		if n % 2 == 1 {
+			let mut b = b.clone();
+			a.shl_words(1);
+			b.shl_words(1);
+			let (q, mut r) = UBigNumber::div_2n_1n(a, &b, n + 1);
+			r.shr_words(1);
+			return (q, r);
+		}
+
+		let half = n / 2;
+		let b1 = b.high_words(half);
+		let b2 = b.low_words(half);
+
+		let (q1, r) = UBigNumber::div_3n_2n(a.high_words(n), a.sub_number_clamped(half..n), b, &b1, &b2, half);
+		let (q2, r) = UBigNumber::div_3n_2n(r, a.low_words(half), b, &b1, &b2, half);
+
+		let mut q = q1;
+		q.shl_words(half);
+		(q + q2, r)
 	}
 
-	pub fn quotient_and_remainder(&self, divisor: &Self) -> (Self, Self) {
-		// println!("Dividend: {:?}", self);
-		// println!("Divisor: {:?}", divisor);
+	/// Divides `a12 * B^n + a3` by `b = b1 * B^n + b2`, where `b1` and `b2` have `n` words, the top bit of `b1` is
+	/// set, and `a12 < b * B^n`
+	fn div_3n_2n(a12: UBigNumber, a3: UBigNumber, b: &UBigNumber, b1: &UBigNumber, b2: &UBigNumber, n: usize) -> (UBigNumber, UBigNumber) {
 
-		if divisor.is_zero() {
-			panic!("Division by zero")
-		} else if self < divisor {
-			(UBigNumber::zero(), self.clone())
-		} else if self == divisor {
-			(UBigNumber::one(), UBigNumber::zero())
+		// Estimate the quotient by dividing the top of `a` by the top of `b`. This is never too small, and at most
+		// two too big.
+		let (mut q, mut r) = if a12.high_words(n) == *b1 {
+			// the quotient would be B^n, which is too big, so take B^n - 1, and compute the remainder directly
+			let mut q = UBigNumber::one();
+			q.shl_words(n);
+			let mut b1_shifted = b1.clone();
+			b1_shifted.shl_words(n);
+			(q - UBigNumber::one(), a12 - b1_shifted + b1)
 		} else {
-			if divisor.len() == 1 {
-				let (q, r) = UBigNumber::div_rem_short(self.clone(), divisor[0]);
-				(q, r.into())
-			} else {
-				UBigNumber::div_rem_core(self.clone(), divisor.clone())
-			}
+			UBigNumber::div_2n_1n(a12, b1, n)
+		};
+
+		r.shl_words(n);
+		r += a3;
+
+		let d = &q * b2;
+		while r < d {
+			q -= UBigNumber::one();
+			r += b;
+		}
+
+		(q, r - d)
+	}
+
+	/// The number made of the lowest `n` words of this number
+	fn low_words(&self, n: usize) -> UBigNumber {
+		self.sub_number_clamped(0..n)
+	}
+
+	/// The number made of all but the lowest `n` words of this number
+	fn high_words(&self, n: usize) -> UBigNumber {
+		self.sub_number_clamped(n..self.len())
+	}
+
+	/// Like `sub_number`, but any part of the range past the end of the words counts as zeroes
+	fn sub_number_clamped(&self, range: Range<usize>) -> UBigNumber {
+		let end = range.end.min(self.len());
+		if range.start >= end {
+			UBigNumber::zero()
+		} else {
+			self.sub_number(range.start..end)
 		}
 	}
 }
@@ -349,26 +677,18 @@ impl From<&str> for UBigNumber {
 	}
 }
 
-impl From<Word> for UBigNumber {
-	fn from(value: Word) -> Self {
-		UBigNumber { words: vec![value] }
-	}
-}
+forward_from_primitive!(impl for UBigNumber, u8, u16, u32, u64, u128, usize);
+forward_try_into_primitive!(impl for UBigNumber, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
 
 // MARK: Bitwise Operations
 
-impl BitOr for UBigNumber {
-	type Output = UBigNumber;
-
-	fn bitor(self, rhs: Self) -> Self::Output {
-		let mut or = self;
-		or |= rhs;
-		or
-	}
-}
+forward_binop!(impl commutative BitAnd, bitand, BitAndAssign, bitand_assign for UBigNumber);
+forward_binop!(impl commutative BitOr, bitor, BitOrAssign, bitor_assign for UBigNumber);
+forward_binop!(impl commutative BitXor, bitxor, BitXorAssign, bitxor_assign for UBigNumber);
+forward_shifts!(impl for UBigNumber, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
 
-impl BitOrAssign for UBigNumber {
-	fn bitor_assign(&mut self, rhs: Self) {
+impl BitOrAssign<&UBigNumber> for UBigNumber {
+	fn bitor_assign(&mut self, rhs: &UBigNumber) {
 		self.extend(max(self.len(), rhs.len()));
 
 		for i in 0..self.len() {
@@ -379,83 +699,28 @@ impl BitOrAssign for UBigNumber {
 	}
 }
 
-impl Shl<Word> for UBigNumber {
-	type Output = UBigNumber;
-
-	fn shl(self, rhs: Word) -> Self::Output {
-		let mut shifted = self;
-		shifted <<= rhs;
-		shifted
-	}
-}
-
-impl ShlAssign<Word> for UBigNumber {
-	fn shl_assign(&mut self, rhs: Word) {
-		let word_shift = rhs as usize / WORD_BIT_COUNT;
-		let bit_shift = rhs as usize % WORD_BIT_COUNT;
-		
-		self.extend(self.len() + word_shift + 1);
-
-		// let's first shift the words
-		for i in word_shift..self.len() {
-			self[i] = self[i - word_shift];
-		}
-		for i in 0..word_shift {
-			self[i] = 0;
-		}
-
-		debug_assert!(self[self.len() - 1] == 0);
+impl BitAndAssign<&UBigNumber> for UBigNumber {
+	fn bitand_assign(&mut self, rhs: &UBigNumber) {
+		// every word past the end of the shorter number is zero
+		self.words.truncate(rhs.len());
 
-		// now we shift the bits!
-		for i in (1..self.len()).rev() {
-			self[i] <<= bit_shift;
-			
-			// take the upper bit_shift bits of self[i - 1] and put them in the lower ones here
-			// self[i] |= self[i - 1] >> (WORD_BIT_COUNT - bit_shift);
-			self[i] |= self[i - 1].wrapping_shr((WORD_BIT_COUNT - bit_shift) as u32)
+		for i in 0..self.len() {
+			self[i] &= rhs[i]
 		}
 
-		self[0] <<= bit_shift;
-
-		self.normalize();
-	}
-}
-
-impl Shr<Word> for UBigNumber {
-	type Output = UBigNumber;
-	
-	fn shr(self, rhs: Word) -> Self::Output {
-		let mut shifted = self;
-		shifted >>= rhs;
-		shifted
+		self.normalize()
 	}
 }
 
-impl ShrAssign<Word> for UBigNumber {
-	fn shr_assign(&mut self, rhs: Word) {
-		let word_shift = rhs as usize / WORD_BIT_COUNT;
-		let bit_shift = rhs as usize % WORD_BIT_COUNT;
-
-		if word_shift >= self.len() {
-			self.words = vec![0];
-			return; // we just died :(
-		}
-
-		// let's first shift the words
-		for i in 0..(self.len() - word_shift) {
-			self[i] = self[i + word_shift];
-		}
-		for i in (self.len() - word_shift)..self.len() {
-			self[i] = 0;
-		}
+impl BitXorAssign<&UBigNumber> for UBigNumber {
+	fn bitxor_assign(&mut self, rhs: &UBigNumber) {
+		self.extend(max(self.len(), rhs.len()));
 
-		for i in 0..(self.len() - 1) {
-			self[i] >>= bit_shift;
-			self[i] |= self[i + 1].wrapping_shl((WORD_BIT_COUNT - bit_shift).try_into().unwrap())
+		for i in 0..self.len() {
+			self[i] ^= rhs.safe_word(i)
 		}
 
-		let size = self.len(); // doing this to avoid violating borrowing
-		self[size - 1] >>= bit_shift;
+		self.normalize()
 	}
 }
 
@@ -491,97 +756,51 @@ impl Eq for UBigNumber { /* Nothing here I guess? */ }
 
 // MARK: Arithmetic
 
-impl Add for UBigNumber {
-	type Output = UBigNumber;
-
-	fn add(self, rhs: Self) -> Self::Output {
-		let mut sum = self;
-		sum += rhs;
-		sum
-	}
-}
+forward_binop!(impl commutative Add, add, AddAssign, add_assign for UBigNumber);
+forward_binop!(impl Sub, sub, SubAssign, sub_assign for UBigNumber);
+forward_binop!(impl commutative Mul, mul, MulAssign, mul_assign for UBigNumber);
+forward_binop!(impl Div, div, DivAssign, div_assign for UBigNumber);
+forward_binop!(impl Rem, rem, RemAssign, rem_assign for UBigNumber);
+forward_primitive_ops!(impl for UBigNumber, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
 
-impl AddAssign for UBigNumber {
-	fn add_assign(&mut self, rhs: Self) {
+impl AddAssign<&UBigNumber> for UBigNumber {
+	fn add_assign(&mut self, rhs: &UBigNumber) {
 		self.custom_add(rhs, true);
 	}
 }
 
-impl Sub for UBigNumber {
-	type Output = UBigNumber;
-
-	fn sub(self, rhs: Self) -> Self::Output {
-		let mut diff = self;
-		diff -= rhs;
-		diff
-	}
-}
-
-impl SubAssign for UBigNumber {
-	fn sub_assign(&mut self, mut rhs: Self) {
-		rhs.extend(self.len());
-		self.extend(rhs.len());
-
-		// negate rhs using 2's complement
-		let mut complement = UBigNumber { words: rhs.words.iter().map(|w| !w).collect() };
-		complement.custom_add(UBigNumber::one(), false);
-		self.custom_add(complement, false);
-		self.normalize()
-	}
-}
-
-impl Mul for UBigNumber {
-	type Output = UBigNumber;
-
-	fn mul(mut self, rhs: Self) -> Self::Output {
-		self *= rhs;
-		self
+impl SubAssign<&UBigNumber> for UBigNumber {
+	/// Like the primitive integers, this panics in debug builds if the result would be negative. In release builds,
+	/// it wraps around just like `overflowing_sub`.
+	fn sub_assign(&mut self, rhs: &UBigNumber) {
+		let borrow = self.overflowing_sub_assign(rhs);
+		debug_assert!(!borrow, "attempt to subtract with overflow");
 	}
 }
 
-impl MulAssign for UBigNumber {
-	fn mul_assign(&mut self, rhs: Self) {
-		if *self == UBigNumber::zero() || rhs == UBigNumber::zero() {
+impl MulAssign<&UBigNumber> for UBigNumber {
+	fn mul_assign(&mut self, rhs: &UBigNumber) {
+		if self.is_zero() || rhs.is_zero() {
 			self.words = vec![0];
 		} else if *self == UBigNumber::one() {
-			self.words = rhs.words;
-		} else if rhs == UBigNumber::one() {
-			return;
-		} else {
-			self.words = int_utility::word_mul(self.words.clone(), rhs.words);
+			self.words = rhs.words.clone();
+		} else if *rhs != UBigNumber::one() {
+			self.words = int_utility::mul(&self.words, &rhs.words);
 			self.normalize()
 		}
 	}
 }
 
-impl Div for UBigNumber {
-	type Output = UBigNumber;
-
-	fn div(self, rhs: Self) -> Self::Output {
-		match self.quotient_and_remainder(&rhs) { (q, _) => q }
+impl DivAssign<&UBigNumber> for UBigNumber {
+	fn div_assign(&mut self, rhs: &UBigNumber) {
+		let (quotient, _) = self.quotient_and_remainder(rhs);
+		*self = quotient;
 	}
 }
 
-impl DivAssign for UBigNumber {
-	fn div_assign(&mut self, rhs: Self) {
-		let (quotient, _) = self.quotient_and_remainder(&rhs);
-		self.words = quotient.words;
-		self.normalize()
+impl RemAssign<&UBigNumber> for UBigNumber {
+	fn rem_assign(&mut self, rhs: &UBigNumber) {
+		let (_, remainder) = self.quotient_and_remainder(rhs);
+		*self = remainder;
 	}
 }
-
-impl Rem for UBigNumber {
-	type Output = UBigNumber;
-
-	fn rem(self, rhs: Self) -> Self::Output {
-		match self.quotient_and_remainder(&rhs) { (_, r) => r }
-	}
-}
-
-impl RemAssign for UBigNumber {
-	fn rem_assign(&mut self, rhs: Self) {
-		let (_, remainder) = self.quotient_and_remainder(&rhs);
-		self.words = remainder.words;
-		self.normalize()
-	}
-}
\ No newline at end of file